@@ -1,7 +1,6 @@
 use std::error::Error;
-use std::io::Cursor;
-use tokio::stream::StreamExt;
-use tunein::Outline;
+use tunein::async_client::{AsyncClient, HyperTransport};
+use tunein::{Document, Outline, Url};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -9,35 +8,24 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .nth(1)
         .unwrap_or_else(|| tunein::request::BROWSE_URI.to_string());
 
-    let client = hyper::Client::new();
-    let response = client.get(uri.parse()?).await?;
-    let mut body = response.into_body();
-
-    let mut buffer = Vec::new();
-    while let Some(bytes) = body.next().await {
-        buffer.extend_from_slice(&bytes?);
-    }
-
-    let document = tunein::read(Cursor::new(buffer))?;
+    let client = AsyncClient::new(HyperTransport::new());
+    let document = client.fetch(&Url::parse(&uri)?).await?;
 
     println!("{}\n-----", document.head.title);
 
-    print_outlines(&document.outlines, 0);
+    print_outlines(&document);
 
     Ok(())
 }
 
-fn print_outlines(outlines: &[Outline], depth: usize) {
-    for outline in outlines {
-        print!("{:>indent$}", "", indent = 4 * depth);
-        match outline {
-            Outline::Group(group) => {
-                println!("{}:", group.text);
-                print_outlines(&group.outlines, depth + 1);
-            }
+fn print_outlines(document: &Document) {
+    for entry in document.iter() {
+        print!("{:>indent$}", "", indent = 4 * entry.depth);
+        match entry.outline {
+            Outline::Group(group) => println!("{}:", group.text),
             Outline::Link(link) => println!("{} - {}", link.text, link.url),
             Outline::Audio(audio) => println!("{} - {}", audio.text, audio.url),
-            Outline::Text(text) => println!("{}", text),
+            outline => println!("{}", outline.text()),
         }
     }
 }
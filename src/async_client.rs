@@ -0,0 +1,217 @@
+use crate::client::{request_url, status_error, Response};
+use crate::common::{Audio, Document};
+use crate::error::{Error, ErrorKind};
+use crate::guide_id::GuideId;
+use crate::paging::AsyncPages;
+use crate::policy::{exceeds, timeout_error, Governor, Outcome, Policy, Step};
+use crate::read::read_async;
+use crate::request::{check_base, Render, Request};
+use crate::tune::{audio_url, parse_response, Stream};
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use url::Url;
+
+#[cfg(feature = "hyper-client")]
+pub use crate::hyper_transport::HyperTransport;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Asynchronous counterpart of [`Transport`](crate::client::Transport).
+pub trait AsyncTransport {
+    type Body: AsyncRead + Unpin + Send;
+
+    fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, io::Result<Response<Self::Body>>>;
+
+    /// Asynchronous counterpart of
+    /// [`Transport::get_with_headers`](crate::client::Transport::get_with_headers).
+    fn get_with_headers<'a>(
+        &'a self,
+        url: &'a Url,
+        _headers: &'a [(String, String)],
+    ) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        self.get(url)
+    }
+}
+
+impl<T: AsyncTransport + Sync + ?Sized> AsyncTransport for &T {
+    type Body = T::Body;
+
+    fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        (**self).get(url)
+    }
+
+    fn get_with_headers<'a>(
+        &'a self,
+        url: &'a Url,
+        headers: &'a [(String, String)],
+    ) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        (**self).get_with_headers(url, headers)
+    }
+}
+
+/// Asynchronous counterpart of [`Client`](crate::client::Client).
+pub struct AsyncClient<T> {
+    transport: T,
+    base: Option<Url>,
+    governor: Governor,
+}
+
+impl<T: AsyncTransport> AsyncClient<T> {
+    pub fn new(transport: T) -> AsyncClient<T> {
+        AsyncClient {
+            transport,
+            base: None,
+            governor: Governor::new(Policy::default()),
+        }
+    }
+
+    /// Sends all requests to `base` instead of the TuneIn server.
+    ///
+    /// Fails for URLs which can't be a base, such as `mailto:` URLs.
+    pub fn with_base(transport: T, base: Url) -> Result<AsyncClient<T>, Error> {
+        Ok(AsyncClient {
+            transport,
+            base: Some(check_base(base)?),
+            governor: Governor::new(Policy::default()),
+        })
+    }
+
+    /// Sends requests following `policy`, see [`policy`](crate::policy).
+    ///
+    /// Unlike with the blocking client, an attempt still running when the
+    /// timeout expires is cancelled.
+    pub fn with_policy(mut self, policy: Policy) -> AsyncClient<T> {
+        self.governor = Governor::new(policy);
+        self
+    }
+
+    pub fn policy(&self) -> &Policy {
+        self.governor.policy()
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Root of the directory.
+    pub async fn browse(&self) -> Result<Document, Error> {
+        self.get(&Request::browse()).await
+    }
+
+    pub async fn browse_category(&self, category: &str) -> Result<Document, Error> {
+        self.get(&Request::browse().category(category)).await
+    }
+
+    pub async fn browse_id(&self, id: &GuideId) -> Result<Document, Error> {
+        self.get(&Request::browse().id(id)).await
+    }
+
+    pub async fn search(&self, query: &str) -> Result<Document, Error> {
+        self.get(&Request::search(query)).await
+    }
+
+    pub async fn describe(&self, id: &GuideId) -> Result<Document, Error> {
+        self.get(&Request::describe(id)).await
+    }
+
+    /// Streams of a station or topic, rendered as OPML audio outlines.
+    pub async fn tune(&self, id: &GuideId) -> Result<Document, Error> {
+        self.get(&Request::tune(id).render(Render::Xml)).await
+    }
+
+    pub async fn get(&self, request: &Request) -> Result<Document, Error> {
+        self.fetch(&request_url(&self.base, request)).await
+    }
+
+    /// Fetches a document by URL, e.g. one found in a [`Link`](crate::Link).
+    pub async fn fetch(&self, url: &Url) -> Result<Document, Error> {
+        self.send(url, || async move {
+            let response = check_response(self.transport.get(url).await).await?;
+            read_async(response.body).await
+        })
+        .await
+    }
+
+    /// Pages of a listing, following its continuation links.
+    pub fn pages(&self, request: &Request) -> AsyncPages<'_, T> {
+        AsyncPages::new(self, request_url(&self.base, request))
+    }
+
+    /// Pages of a listing starting at `url`, following its continuation links.
+    pub fn fetch_pages(&self, url: &Url) -> AsyncPages<'_, T> {
+        AsyncPages::new(self, url.clone())
+    }
+
+    /// Fetches a `Tune.ashx` playlist and returns its streams in playlist order.
+    pub async fn resolve(&self, url: &Url) -> Result<Vec<Stream>, Error> {
+        self.send(url, || async move {
+            let mut response = check_response(self.transport.get(url).await).await?;
+            let mut content = Vec::new();
+            response
+                .body
+                .read_to_end(&mut content)
+                .await
+                .map_err(|error| Error::new(ErrorKind::Io(error)))?;
+            parse_response(&content)
+        })
+        .await
+    }
+
+    /// Resolves the URL of an audio outline, using its bitrate and format as hints.
+    pub async fn resolve_audio(&self, audio: &Audio) -> Result<Vec<Stream>, Error> {
+        let streams = self.resolve(&audio_url(audio)?).await?;
+        Ok(streams
+            .into_iter()
+            .map(|stream| stream.with_audio_hints(audio))
+            .collect())
+    }
+
+    /// Runs `attempt` with the rate limit, retries and timeout of the policy.
+    async fn send<R, F, A>(&self, url: &Url, attempt: F) -> Result<R, Error>
+    where
+        F: Fn() -> A,
+        A: Future<Output = Result<R, Error>>,
+        Result<R, Error>: Outcome,
+    {
+        let deadline = self.governor.deadline();
+        let mut retry = 0;
+        loop {
+            let wait = self.governor.acquire(url);
+            if exceeds(deadline, wait) {
+                return Err(timeout_error());
+            }
+            tokio::time::delay_for(wait).await;
+            let outcome = match deadline {
+                Some(deadline) => {
+                    let deadline = tokio::time::Instant::from_std(deadline);
+                    match tokio::time::timeout_at(deadline, attempt()).await {
+                        Ok(outcome) => outcome,
+                        Err(_) => Err(timeout_error()),
+                    }
+                }
+                None => attempt().await,
+            };
+            match self.governor.next(&outcome, retry, deadline) {
+                Step::Retry(delay) => tokio::time::delay_for(delay).await,
+                Step::Stop => return outcome,
+            }
+            retry += 1;
+        }
+    }
+}
+
+/// Asynchronous counterpart of [`check_response`](crate::client::check_response).
+async fn check_response<B: AsyncRead + Unpin>(
+    response: io::Result<Response<B>>,
+) -> Result<Response<B>, Error> {
+    let mut response = response.map_err(|error| Error::new(ErrorKind::Io(error)))?;
+    if response.is_success() {
+        Ok(response)
+    } else {
+        let mut body = Vec::new();
+        let body = response.body.read_to_end(&mut body).await.map(|_| body);
+        Err(status_error(response.status, body.ok().as_deref()))
+    }
+}
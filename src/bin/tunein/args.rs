@@ -0,0 +1,242 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use tunein::playlist::PlaylistKind;
+use tunein::request::{Render, Request};
+use tunein::{Format, GuideId, Url};
+
+pub const USAGE: &str = "\
+Usage: tunein <command> [options]
+
+Commands:
+    browse [id|category]   Browse the directory, from the root by default
+    search <query>         Search stations, shows and topics
+    describe <guide_id>    Describe a station, show or topic
+    tune <guide_id>        List the streams of a station or topic
+    export [id|category]   Write the directory as OPML, or as a playlist
+
+Any id, category, guide id or single-word query may be replaced by a URL, or
+by the path of a local OPML or JSON file.
+
+Options:
+    -o, --output <tree|json|tsv>   Output format, tree by default
+    -f, --format <formats>         Keep streams in one of these formats, e.g. mp3,aac
+    --min-bitrate <kbps>           Keep streams with at least this bitrate
+    --min-reliability <percent>    Keep streams with at least this reliability
+    -d, --depth <depth>            Follow links this deep when exporting
+    -p, --playlist <m3u|pls|xspf>  Export the streams as a playlist
+    --resolve                      Write the resolved stream URLs in playlists
+    -i, --interactive              Browse interactively in the terminal
+    -h, --help                     Print this help
+
+In interactive mode, Enter follows a link, Backspace goes back and / searches
+the current list. When standard input isn't a terminal, the keys are read
+from it, one name or text per line, and the final screen is printed.
+";
+
+#[derive(Debug)]
+pub struct Args {
+    pub command: Command,
+    pub source: Source,
+    pub output: Output,
+    pub filter: Filter,
+    pub depth: usize,
+    pub interactive: bool,
+    pub playlist: Option<PlaylistKind>,
+    pub resolve: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    Browse,
+    Search,
+    Describe,
+    Tune,
+    Export,
+}
+
+/// Where the document comes from.
+#[derive(Debug)]
+pub enum Source {
+    Url(Url),
+    File(PathBuf),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Output {
+    Tree,
+    Json,
+    Tsv,
+}
+
+/// Conditions on audio outlines, which are removed when they don't meet them.
+#[derive(Debug, Default)]
+pub struct Filter {
+    pub formats: Vec<Format>,
+    pub min_bitrate: Option<u16>,
+    pub min_reliability: Option<u16>,
+}
+
+impl Args {
+    /// Parses the arguments, without the program name. Returns `Ok(None)`
+    /// when help is requested.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Args>, String> {
+        let mut positional = vec![];
+        let mut output = Output::Tree;
+        let mut filter = Filter::default();
+        let mut depth = 0;
+        let mut interactive = false;
+        let mut playlist = None;
+        let mut resolve = false;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if arg.starts_with("--") => (name.to_string(), Some(value)),
+                _ => (arg.clone(), None),
+            };
+            let mut value = || match inline_value {
+                Some(value) => Ok(value.to_string()),
+                None => args.next().ok_or(format!("Missing value of {}", name)),
+            };
+            match name.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "-o" | "--output" => output = value()?.parse()?,
+                "-f" | "--format" => {
+                    let formats = value()?;
+                    for format in formats.split(',').filter(|format| !format.is_empty()) {
+                        filter.formats.push(stream_format(format)?);
+                    }
+                }
+                "--min-bitrate" => filter.min_bitrate = Some(number(&name, &value()?)?),
+                "--min-reliability" => filter.min_reliability = Some(number(&name, &value()?)?),
+                "-d" | "--depth" => depth = number(&name, &value()?)?,
+                "-i" | "--interactive" => interactive = true,
+                "-p" | "--playlist" => playlist = Some(playlist_kind(&value()?)?),
+                "--resolve" => resolve = true,
+                option if option.starts_with('-') && option.len() > 1 => {
+                    return Err(format!("Unknown option {}", option))
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let command: Command = positional.next().ok_or("Missing command")?.parse()?;
+        if interactive && command == Command::Export {
+            return Err("Export can't be interactive".to_string());
+        }
+        if playlist.is_some() && command != Command::Export {
+            return Err("Only export writes playlists".to_string());
+        }
+        if resolve && playlist.is_none() {
+            return Err("Missing --playlist to resolve streams for".to_string());
+        }
+        let rest: Vec<String> = positional.collect();
+        let source = match (command, rest.as_slice()) {
+            (Command::Browse, []) | (Command::Export, []) => Source::Url(Request::browse().url()),
+            (Command::Browse, [target]) | (Command::Export, [target]) => {
+                source(target, |target| match target.parse::<GuideId>() {
+                    Ok(id) => Ok(Request::browse().id(&id)),
+                    Err(_) => Ok(Request::browse().category(target)),
+                })?
+            }
+            (Command::Search, []) => return Err("Missing search query".to_string()),
+            (Command::Search, [target]) => source(target, |query| Ok(Request::search(query)))?,
+            (Command::Search, words) => Source::Url(Request::search(&words.join(" ")).url()),
+            (Command::Describe, [target]) => {
+                source(target, |target| Ok(Request::describe(&guide_id(target)?)))?
+            }
+            (Command::Tune, [target]) => source(target, |target| {
+                Ok(Request::tune(&guide_id(target)?).render(Render::Xml))
+            })?,
+            (Command::Describe, []) | (Command::Tune, []) => {
+                return Err("Missing guide id".to_string())
+            }
+            (_, [_, extra, ..]) => return Err(format!("Unexpected argument {}", extra)),
+        };
+
+        Ok(Some(Args {
+            command,
+            source,
+            output,
+            filter,
+            depth,
+            interactive,
+            playlist,
+            resolve,
+        }))
+    }
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Command, String> {
+        match value {
+            "browse" => Ok(Command::Browse),
+            "search" => Ok(Command::Search),
+            "describe" => Ok(Command::Describe),
+            "tune" => Ok(Command::Tune),
+            "export" => Ok(Command::Export),
+            _ => Err(format!("Unknown command {}", value)),
+        }
+    }
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Output, String> {
+        match value {
+            "tree" => Ok(Output::Tree),
+            "json" => Ok(Output::Json),
+            "tsv" => Ok(Output::Tsv),
+            _ => Err(format!("Unknown output format {}", value)),
+        }
+    }
+}
+
+fn playlist_kind(value: &str) -> Result<PlaylistKind, String> {
+    match value {
+        "m3u" => Ok(PlaylistKind::M3U),
+        "pls" => Ok(PlaylistKind::PLS),
+        "xspf" => Ok(PlaylistKind::XSPF),
+        _ => Err(format!("Unknown playlist format {}", value)),
+    }
+}
+
+fn stream_format(value: &str) -> Result<Format, String> {
+    match value.parse() {
+        Ok(Format::Other(_)) | Err(_) => Err(format!("Unknown stream format {}", value)),
+        Ok(format) => Ok(format),
+    }
+}
+
+/// URLs and existing files are taken as they are, anything else is turned
+/// into a request by `request`.
+fn source<F>(target: &str, request: F) -> Result<Source, String>
+where
+    F: FnOnce(&str) -> Result<Request, String>,
+{
+    if target.starts_with("http://") || target.starts_with("https://") {
+        let url =
+            Url::parse(target).map_err(|error| format!("Invalid URL {}: {}", target, error))?;
+        Ok(Source::Url(url))
+    } else if PathBuf::from(target).is_file() {
+        Ok(Source::File(target.into()))
+    } else {
+        Ok(Source::Url(request(target)?.url()))
+    }
+}
+
+fn guide_id(value: &str) -> Result<GuideId, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid guide id {}", value))
+}
+
+fn number<N: FromStr>(name: &str, value: &str) -> Result<N, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value of {}: {}", name, value))
+}
@@ -0,0 +1,357 @@
+use crate::args::Filter;
+use crate::output::detail_fields;
+use tunein::{Document, Outline, Url};
+
+/// Keys the browser reacts to, independent of the terminal library.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Enter,
+    Backspace,
+    Esc,
+    Char(char),
+}
+
+/// Fetches the document a link points to.
+pub type Loader<'a> = Box<dyn FnMut(&Url) -> Result<Document, String> + 'a>;
+
+/// State of the interactive browser: a stack of documents, each shown as a
+/// list of outlines, the last one being the current.
+pub struct Browser<'a> {
+    levels: Vec<Level>,
+    loader: Loader<'a>,
+    filter: &'a Filter,
+    /// Query being typed after `/`.
+    searching: bool,
+    message: Option<String>,
+    pub quit: bool,
+}
+
+struct Level {
+    url: Url,
+    document: Document,
+    query: String,
+    rows: Vec<Row>,
+    selected: usize,
+    /// First row on screen.
+    offset: usize,
+}
+
+/// Outline on screen, located by the indices leading to it in the document
+/// of its level.
+struct Row {
+    path: Vec<usize>,
+}
+
+const LIST_WIDTH: f32 = 0.6;
+const HELP: &str = "Enter: open  Backspace: back  /: search  q: quit";
+
+impl<'a> Browser<'a> {
+    pub fn new(
+        url: Url,
+        document: Document,
+        loader: Loader<'a>,
+        filter: &'a Filter,
+    ) -> Browser<'a> {
+        let mut browser = Browser {
+            levels: vec![],
+            loader,
+            filter,
+            searching: false,
+            message: None,
+            quit: false,
+        };
+        browser.push(url, document);
+        browser
+    }
+
+    pub fn handle(&mut self, key: Key, height: usize) {
+        self.message = None;
+        let page = height.saturating_sub(3).max(1);
+        if self.searching {
+            self.search(key);
+            return;
+        }
+        match key {
+            Key::Up => self.select(-1),
+            Key::Down => self.select(1),
+            Key::PageUp => self.select(-(page as isize)),
+            Key::PageDown => self.select(page as isize),
+            Key::Enter => self.open(),
+            Key::Backspace if self.levels.len() > 1 => {
+                self.levels.pop();
+            }
+            Key::Backspace => self.message = Some("Already at the top".to_string()),
+            Key::Esc => self.set_query(String::new()),
+            Key::Char('/') => {
+                self.searching = true;
+                self.set_query(String::new());
+            }
+            Key::Char('q') => self.quit = true,
+            Key::Char('j') => self.select(1),
+            Key::Char('k') => self.select(-1),
+            Key::Char(_) => {}
+        }
+    }
+
+    /// Lines of the screen, `width` characters wide at most.
+    pub fn render(&mut self, width: usize, height: usize) -> Vec<String> {
+        let list_width = (width as f32 * LIST_WIDTH) as usize;
+        let details_width = width.saturating_sub(list_width + 3);
+        let body_height = height.saturating_sub(3);
+
+        let level = self.levels.last_mut().expect("Browser has a level");
+        if level.selected < level.offset {
+            level.offset = level.selected;
+        } else if level.selected >= level.offset + body_height {
+            level.offset = level.selected + 1 - body_height;
+        }
+        let level = self.current();
+
+        let titles: Vec<_> = self
+            .levels
+            .iter()
+            .map(|level| level.document.head.title.as_str())
+            .collect();
+        let mut lines = vec![truncate(&titles.join(" > "), width), "-".repeat(width)];
+
+        let details = match level.rows.get(level.selected) {
+            Some(row) => wrap(&details(level.outline(row)), details_width),
+            None => vec![],
+        };
+        for line in 0..body_height {
+            let index = level.offset + line;
+            let row = match level.rows.get(index) {
+                Some(row) => {
+                    let marker = if index == level.selected { "> " } else { "  " };
+                    let indent = "  ".repeat(row.path.len() - 1);
+                    format!("{}{}{}", marker, indent, label(level.outline(row)))
+                }
+                None if index == 0 => "  (empty)".to_string(),
+                None => String::new(),
+            };
+            let detail = details.get(line).map(String::as_str).unwrap_or_default();
+            let row = pad(&truncate(&row, list_width), list_width);
+            lines.push(format!("{} | {}", row, detail).trim_end().to_string());
+        }
+
+        let status = if self.searching {
+            format!("/{}", level.query)
+        } else if let Some(message) = &self.message {
+            message.clone()
+        } else if !level.query.is_empty() {
+            format!("Search: {}  Esc: clear", level.query)
+        } else {
+            HELP.to_string()
+        };
+        lines.push(truncate(&status, width));
+        lines
+    }
+
+    fn current(&self) -> &Level {
+        self.levels.last().expect("Browser has a level")
+    }
+
+    fn current_mut(&mut self) -> &mut Level {
+        self.levels.last_mut().expect("Browser has a level")
+    }
+
+    fn push(&mut self, url: Url, mut document: Document) {
+        self.filter.apply(&mut document);
+        let mut level = Level {
+            url,
+            document,
+            query: String::new(),
+            rows: vec![],
+            selected: 0,
+            offset: 0,
+        };
+        level.update_rows();
+        self.levels.push(level);
+    }
+
+    fn select(&mut self, step: isize) {
+        let level = self.current_mut();
+        let last = level.rows.len().saturating_sub(1) as isize;
+        level.selected = (level.selected as isize + step).clamp(0, last) as usize;
+    }
+
+    fn open(&mut self) {
+        let level = self.current();
+        let link = match level.rows.get(level.selected).map(|row| level.outline(row)) {
+            Some(Outline::Link(link)) => link,
+            _ => return,
+        };
+        let url = match level.url.join(&link.url) {
+            Ok(url) => url,
+            Err(error) => {
+                self.message = Some(format!("Invalid URL {}: {}", link.url, error));
+                return;
+            }
+        };
+        match (self.loader)(&url)
+            .and_then(|document| document.into_result().map_err(|error| error.to_string()))
+        {
+            Ok(document) => self.push(url, document),
+            Err(error) => self.message = Some(error),
+        }
+    }
+
+    fn search(&mut self, key: Key) {
+        let mut query = self.current().query.clone();
+        match key {
+            Key::Char(c) => query.push(c),
+            Key::Backspace => {
+                query.pop();
+            }
+            Key::Enter => self.searching = false,
+            Key::Esc => {
+                self.searching = false;
+                query.clear();
+            }
+            _ => return,
+        }
+        self.set_query(query);
+    }
+
+    fn set_query(&mut self, query: String) {
+        let level = self.current_mut();
+        if level.query != query {
+            level.query = query;
+            level.update_rows();
+        }
+    }
+}
+
+impl Level {
+    /// Rows of the outlines matching the query, at any depth, with the
+    /// groups leading to them.
+    fn update_rows(&mut self) {
+        let mut rows = vec![];
+        push_rows(
+            &self.document.outlines,
+            &[],
+            &self.query.to_lowercase(),
+            &mut rows,
+        );
+        self.rows = rows;
+        self.selected = 0;
+        self.offset = 0;
+    }
+
+    fn outline(&self, row: &Row) -> &Outline {
+        outline_at(&self.document.outlines, &row.path)
+    }
+}
+
+/// Adds the rows of `outlines` matching `query`, or of all of them if it's
+/// empty. Groups are handled as in [`Document::filter`]: a matching group is
+/// kept whole, others only lead to their matching descendants.
+fn push_rows(outlines: &[Outline], parent: &[usize], query: &str, rows: &mut Vec<Row>) {
+    for (index, outline) in outlines.iter().enumerate() {
+        let path = [parent, &[index]].concat();
+        let matches = query.is_empty() || outline.text().to_lowercase().contains(query);
+        match outline {
+            Outline::Group(group) => {
+                let start = rows.len();
+                let query = if matches { "" } else { query };
+                push_rows(&group.outlines, &path, query, rows);
+                if matches || rows.len() > start {
+                    rows.insert(start, Row { path });
+                }
+            }
+            _ if matches => rows.push(Row { path }),
+            _ => {}
+        }
+    }
+}
+
+fn outline_at<'a>(outlines: &'a [Outline], path: &[usize]) -> &'a Outline {
+    let (first, rest) = path.split_first().expect("Path is not empty");
+    match (&outlines[*first], rest) {
+        (outline, []) => outline,
+        (Outline::Group(group), rest) => outline_at(&group.outlines, rest),
+        _ => unreachable!("Path leads through groups"),
+    }
+}
+
+fn label(outline: &Outline) -> String {
+    match outline {
+        Outline::Group(group) => format!("{}:", group.text),
+        Outline::Link(link) => format!("{} /", link.text),
+        outline => outline.text().to_string(),
+    }
+}
+
+/// Lines of the details pane for the selected outline.
+fn details(outline: &Outline) -> Vec<String> {
+    let mut lines = vec![outline.text().to_string(), String::new()];
+    let mut field = |name: &str, value: &str| {
+        if !value.is_empty() {
+            lines.push(format!("{}: {}", name, value));
+        }
+    };
+    match outline {
+        Outline::Group(group) => field("Outlines", &group.outlines.len().to_string()),
+        Outline::Link(link) => {
+            field("Guide id", &optional(&link.guide_id));
+            field("URL", &link.url);
+        }
+        Outline::Audio(audio) => {
+            field("Subtext", &audio.subtext);
+            field(
+                "Bitrate",
+                &optional(&audio.bitrate.map(|bitrate| format!("{} kbps", bitrate))),
+            );
+            field(
+                "Reliability",
+                &optional(
+                    &audio
+                        .reliability
+                        .map(|reliability| format!("{}%", reliability)),
+                ),
+            );
+            let formats: Vec<_> = audio.formats.iter().map(ToString::to_string).collect();
+            field("Format", &formats.join(", "));
+            field("Guide id", &optional(&audio.guide_id));
+            field("Image", &audio.image);
+            field("URL", &audio.url);
+        }
+        Outline::Object(object) => {
+            for (name, value) in object.detail.iter().flat_map(detail_fields) {
+                field(name, value);
+            }
+        }
+        Outline::Text(_) => {}
+    }
+    lines
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!("{:width$}", text, width = width)
+}
+
+/// Splits lines longer than `width` characters.
+fn wrap(lines: &[String], width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut wrapped = vec![];
+    for line in lines {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.is_empty() {
+            wrapped.push(String::new());
+        }
+        wrapped.extend(chars.chunks(width).map(|chunk| chunk.iter().collect()));
+    }
+    wrapped
+}
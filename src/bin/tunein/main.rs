@@ -0,0 +1,174 @@
+//! `tunein` command-line client, built with the `cli` feature.
+
+mod args;
+mod browser;
+mod output;
+mod transport;
+mod tui;
+
+use args::{Args, Command, Source, USAGE};
+use browser::Browser;
+use crossterm::tty::IsTty;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::exit;
+use std::time::Duration;
+use transport::BlockingTransport;
+use tunein::client::Client;
+use tunein::crawl::Crawler;
+use tunein::paging::DEFAULT_PAGE_LIMIT;
+use tunein::playlist::{Playlist, PlaylistKind};
+use tunein::policy::Policy;
+use tunein::{Document, Url};
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("tunein: {}\n\n{}", message, USAGE);
+            exit(2);
+        }
+    };
+    if let Err(error) = run(&args) {
+        eprintln!("tunein: {}", error);
+        exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    if args.interactive {
+        return interactive(args);
+    }
+    let mut document = load(args)?.into_result()?;
+    args.filter.apply(&mut document);
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match (args.command, args.playlist) {
+        (Command::Export, Some(kind)) => export_playlist(&document, kind, args.resolve, &mut out)?,
+        (Command::Export, None) => {
+            tunein::write(&document, &mut out)?;
+            writeln!(out)?;
+        }
+        _ => output::print(&document, args.output, &mut out)?,
+    }
+    Ok(())
+}
+
+fn load(args: &Args) -> Result<Document, Box<dyn Error>> {
+    let url = match &args.source {
+        Source::File(path) if args.depth > 0 => {
+            return Err(format!("Can't follow links of {}", path.display()).into())
+        }
+        Source::File(path) => return read_file(path),
+        Source::Url(url) => url,
+    };
+
+    let client = client()?;
+    if args.depth > 0 {
+        let crawl = Crawler::new(&client)
+            .max_depth(args.depth)
+            .concurrency(4)
+            .crawl_url(url)?;
+        for failure in &crawl.failures {
+            eprintln!("tunein: {}: {}", failure.url, failure.error);
+        }
+        for url in &crawl.truncated {
+            eprintln!(
+                "tunein: {}: Listing truncated after {} pages",
+                url, DEFAULT_PAGE_LIMIT
+            );
+        }
+        Ok(crawl.document)
+    } else {
+        Ok(client.fetch(url)?)
+    }
+}
+
+fn export_playlist<W: Write>(
+    document: &Document,
+    kind: PlaylistKind,
+    resolve: bool,
+    out: &mut W,
+) -> Result<(), Box<dyn Error>> {
+    let mut playlist = Playlist::from_document(document);
+    if resolve {
+        let client = client()?;
+        playlist = playlist.resolve_with(|audio| match client.resolve_audio(audio) {
+            Ok(streams) => streams.into_iter().next().map(|stream| stream.url),
+            Err(error) => {
+                eprintln!("tunein: {}: {}", audio.text, error);
+                None
+            }
+        });
+    }
+    playlist.write(kind, &mut *out)?;
+    if kind == PlaylistKind::XSPF {
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn interactive(args: &Args) -> Result<(), Box<dyn Error>> {
+    let url = match &args.source {
+        Source::File(path) => Url::from_file_path(std::fs::canonicalize(path)?)
+            .map_err(|_| format!("Invalid path {}", path.display()))?,
+        Source::Url(url) => url.clone(),
+    };
+    let document = load(args)?.into_result()?;
+
+    let mut client = None;
+    let loader = Box::new(move |url: &Url| {
+        fetch(&mut client, url).map_err(|error| format!("{}: {}", url, error))
+    });
+    let mut browser = Browser::new(url, document, loader, &args.filter);
+    if io::stdin().is_tty() {
+        tui::run(&mut browser)?;
+    } else {
+        tui::run_script(&mut browser, io::stdin().lock(), &mut io::stdout().lock())?;
+    }
+    Ok(())
+}
+
+/// Fetches a document, reading `file` URLs from the disk and creating the
+/// client on first use otherwise.
+fn fetch(
+    client: &mut Option<Client<BlockingTransport>>,
+    url: &Url,
+) -> Result<Document, Box<dyn Error>> {
+    if url.scheme() == "file" {
+        let path = url
+            .to_file_path()
+            .map_err(|_| format!("Invalid path {}", url))?;
+        return read_file(&path);
+    }
+    if client.is_none() {
+        *client = Some(self::client()?);
+    }
+    Ok(client.as_ref().unwrap().fetch(url)?)
+}
+
+fn client() -> Result<Client<BlockingTransport>, Box<dyn Error>> {
+    // The transport bounds each attempt, the policy the whole request.
+    let transport = BlockingTransport::new(Duration::from_secs(10))?;
+    let policy = Policy::new().retries(2).timeout(Duration::from_secs(30));
+    Ok(Client::new(transport).with_policy(policy))
+}
+
+fn read_file(path: &Path) -> Result<Document, Box<dyn Error>> {
+    let file = File::open(path)?;
+    if path
+        .extension()
+        .map_or(false, |extension| extension == "json")
+    {
+        Ok(tunein::read_json(file)?)
+    } else {
+        Ok(tunein::read(file)?)
+    }
+}
@@ -0,0 +1,172 @@
+use crate::args::{Filter, Output};
+use std::io::{self, Write};
+use tunein::{Audio, Detail, Document, Outline};
+
+impl Filter {
+    pub fn matches(&self, audio: &Audio) -> bool {
+        let format = self.formats.is_empty()
+            || audio
+                .formats
+                .iter()
+                .any(|format| self.formats.contains(format));
+        let bitrate = at_least(audio.bitrate, self.min_bitrate);
+        let reliability = at_least(audio.reliability, self.min_reliability);
+        format && bitrate && reliability
+    }
+
+    /// Removes the audio outlines not matching the filter.
+    pub fn apply(&self, document: &mut Document) {
+        document.retain(|outline| match outline {
+            Outline::Audio(audio) => self.matches(audio),
+            _ => true,
+        });
+    }
+}
+
+/// An unknown value doesn't meet a minimum.
+fn at_least(value: Option<u16>, min: Option<u16>) -> bool {
+    match (value, min) {
+        (_, None) => true,
+        (Some(value), Some(min)) => value >= min,
+        (None, Some(_)) => false,
+    }
+}
+
+pub fn print<W: Write>(document: &Document, output: Output, out: &mut W) -> io::Result<()> {
+    match output {
+        Output::Tree => tree(document, out),
+        Output::Json => {
+            serde_json::to_writer_pretty(&mut *out, document)?;
+            writeln!(out)
+        }
+        Output::Tsv => tsv(document, out),
+    }
+}
+
+fn tree<W: Write>(document: &Document, out: &mut W) -> io::Result<()> {
+    writeln!(out, "{}\n-----", document.head.title)?;
+    for entry in document.iter() {
+        let indent = 4 * entry.depth;
+        write!(out, "{:>indent$}", "", indent = indent)?;
+        match entry.outline {
+            Outline::Group(group) => writeln!(out, "{}:", group.text)?,
+            Outline::Link(link) => writeln!(out, "{} - {}", link.text, link.url)?,
+            Outline::Audio(audio) => {
+                writeln!(out, "{} [{}] - {}", audio.text, stream(audio), audio.url)?
+            }
+            Outline::Object(object) => {
+                writeln!(out, "{}", object.text)?;
+                for (name, value) in object.detail.iter().flat_map(detail_fields) {
+                    writeln!(
+                        out,
+                        "{:>indent$}{}: {}",
+                        "",
+                        name,
+                        value,
+                        indent = indent + 4
+                    )?;
+                }
+            }
+            outline => writeln!(out, "{}", outline.text())?,
+        }
+    }
+    Ok(())
+}
+
+/// Summary of the stream of an audio outline, such as `mp3, 128 kbps, 91%`.
+fn stream(audio: &Audio) -> String {
+    let mut parts = vec![];
+    if !audio.formats.is_empty() {
+        let formats: Vec<_> = audio.formats.iter().map(ToString::to_string).collect();
+        parts.push(formats.join(","));
+    }
+    if let Some(bitrate) = audio.bitrate {
+        parts.push(format!("{} kbps", bitrate));
+    }
+    if let Some(reliability) = audio.reliability {
+        parts.push(format!("{}%", reliability));
+    }
+    parts.join(", ")
+}
+
+/// Non-empty fields of a detail worth showing.
+pub fn detail_fields(detail: &Detail) -> Vec<(&str, &str)> {
+    let fields = match detail {
+        Detail::Station(station) => vec![
+            ("Call sign", station.call_sign.as_str()),
+            ("Slogan", &station.slogan),
+            ("Description", &station.description),
+            ("Frequency", &station.frequency),
+            ("Band", &station.band),
+            ("Genre", &station.genre_name),
+            ("Location", &station.location),
+            ("Language", &station.language),
+            ("Website", &station.url),
+        ],
+        Detail::Program(program) => vec![
+            ("Description", program.description.as_str()),
+            ("Hosts", &program.hosts),
+            ("Genre", &program.genre_name),
+            ("Location", &program.location),
+            ("Language", &program.language),
+            ("Website", &program.url),
+        ],
+        Detail::Other { fields, .. } => fields.iter().collect(),
+    };
+    fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+}
+
+fn tsv<W: Write>(document: &Document, out: &mut W) -> io::Result<()> {
+    writeln!(
+        out,
+        "depth\ttype\ttext\tguide_id\tformats\tbitrate\treliability\tURL"
+    )?;
+    for entry in document.iter() {
+        let outline = entry.outline;
+        let (kind, url) = match outline {
+            Outline::Group(_) => ("group", ""),
+            Outline::Link(link) => ("link", link.url.as_str()),
+            Outline::Audio(audio) => ("audio", audio.url.as_str()),
+            Outline::Text(_) => ("text", ""),
+            Outline::Object(_) => ("object", ""),
+        };
+        let (formats, bitrate, reliability) = match outline {
+            Outline::Audio(audio) => {
+                let formats: Vec<_> = audio.formats.iter().map(ToString::to_string).collect();
+                (
+                    formats.join(","),
+                    optional(audio.bitrate),
+                    optional(audio.reliability),
+                )
+            }
+            _ => Default::default(),
+        };
+        let guide_id = outline
+            .guide_id()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let fields = [
+            &entry.depth.to_string(),
+            kind,
+            outline.text(),
+            &guide_id,
+            &formats,
+            &bitrate,
+            &reliability,
+            url,
+        ];
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|field| field.replace(['\t', '\n'], " "))
+            .collect();
+        writeln!(out, "{}", fields.join("\t"))?;
+    }
+    Ok(())
+}
+
+fn optional(value: Option<u16>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,65 @@
+use std::io;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::runtime::Runtime;
+use tunein::async_client::{AsyncTransport, HyperTransport};
+use tunein::client::{Response, Transport};
+use tunein::Url;
+
+/// Blocking [`Transport`] running a [`HyperTransport`] on its own runtime.
+pub struct BlockingTransport {
+    runtime: Runtime,
+    transport: HyperTransport,
+    timeout: Duration,
+}
+
+impl BlockingTransport {
+    /// Creates a transport failing requests which take longer than `timeout`,
+    /// as the blocking client can't interrupt them itself.
+    pub fn new(timeout: Duration) -> io::Result<BlockingTransport> {
+        let runtime = tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .enable_all()
+            .build()?;
+        let transport = runtime.enter(HyperTransport::new);
+        Ok(BlockingTransport {
+            runtime,
+            transport,
+            timeout,
+        })
+    }
+}
+
+impl Transport for BlockingTransport {
+    type Body = io::Cursor<Vec<u8>>;
+
+    fn get(&self, url: &Url) -> io::Result<Response<Self::Body>> {
+        self.get_with_headers(url, &[])
+    }
+
+    fn get_with_headers(
+        &self,
+        url: &Url,
+        headers: &[(String, String)],
+    ) -> io::Result<Response<Self::Body>> {
+        let request = async {
+            let mut response = self.transport.get_with_headers(url, headers).await?;
+            let mut body = vec![];
+            response.body.read_to_end(&mut body).await?;
+            Ok(Response {
+                status: response.status,
+                headers: response.headers,
+                body: io::Cursor::new(body),
+            })
+        };
+        self.runtime.handle().block_on(async {
+            match tokio::time::timeout(self.timeout, request).await {
+                Ok(response) => response,
+                Err(_) => Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("No response within {} s", self.timeout.as_secs()),
+                )),
+            }
+        })
+    }
+}
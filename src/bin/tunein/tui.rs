@@ -0,0 +1,94 @@
+use crate::browser::{Browser, Key};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{cursor, execute, queue, style, terminal};
+use std::io::{self, BufRead, Write};
+
+/// Size of the screen printed by [`run_script`].
+const SCRIPT_SIZE: (usize, usize) = (80, 20);
+
+/// Runs the browser in the terminal until it quits.
+pub fn run(browser: &mut Browser) -> crossterm::Result<()> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = event_loop(browser, &mut stdout);
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn event_loop<W: Write>(browser: &mut Browser, out: &mut W) -> crossterm::Result<()> {
+    while !browser.quit {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (usize::from(width), usize::from(height));
+        for (row, line) in browser.render(width, height).iter().enumerate() {
+            queue!(
+                out,
+                cursor::MoveTo(0, row as u16),
+                style::Print(line),
+                terminal::Clear(terminal::ClearType::UntilNewLine)
+            )?;
+        }
+        out.flush()?;
+
+        if let Event::Key(event) = event::read()? {
+            if event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL) {
+                browser.quit = true;
+            } else if let Some(key) = key(event) {
+                browser.handle(key, height);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn key(event: KeyEvent) -> Option<Key> {
+    Some(match event.code {
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::Enter | KeyCode::Right => Key::Enter,
+        KeyCode::Backspace | KeyCode::Left => Key::Backspace,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Char(c) => Key::Char(c),
+        _ => return None,
+    })
+}
+
+/// Drives the browser with keys read from `input`, then prints its screen.
+///
+/// Each line is the name of a key (`up`, `down`, `pageup`, `pagedown`,
+/// `enter`, `backspace` or `esc`), or else characters typed one by one.
+pub fn run_script<R: BufRead, W: Write>(
+    browser: &mut Browser,
+    input: R,
+    out: &mut W,
+) -> io::Result<()> {
+    let (width, height) = SCRIPT_SIZE;
+    for line in input.lines() {
+        let line = line?;
+        let keys = match line.trim() {
+            "up" => vec![Key::Up],
+            "down" => vec![Key::Down],
+            "pageup" => vec![Key::PageUp],
+            "pagedown" => vec![Key::PageDown],
+            "enter" => vec![Key::Enter],
+            "backspace" => vec![Key::Backspace],
+            "esc" => vec![Key::Esc],
+            text => text.chars().map(Key::Char).collect(),
+        };
+        for key in keys {
+            if browser.quit {
+                break;
+            }
+            browser.handle(key, height);
+            // Keeps the selected row in view, as drawing does in the terminal.
+            browser.render(width, height);
+        }
+    }
+    for line in browser.render(width, height) {
+        writeln!(out, "{}", line)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,494 @@
+//! Response cache sitting between a client and its transport.
+//!
+//! [`CachingTransport`] wraps another transport and keeps the bodies of
+//! successful responses, keyed by the normalized request URL. An entry is
+//! served without a request while it's younger than the TTL of its endpoint.
+//! Afterwards it's revalidated with `If-None-Match` and `If-Modified-Since`
+//! when the original response had an `ETag` or `Last-Modified` header.
+//!
+//! When a request fails with a transient error, that is a transport error, a
+//! 429 or 5xx status, or a rate limit or server fault, the last good body is
+//! served instead, however old it is, with a `Warning: 110` header. Other
+//! errors and faults are passed through and not cached.
+//!
+//! ```no_run
+//! # use tunein::client::{Client, Response, Transport};
+//! # struct Http;
+//! # impl Transport for Http {
+//! #     type Body = &'static [u8];
+//! #     fn get(&self, _: &tunein::Url) -> std::io::Result<Response<&'static [u8]>> { Err(std::io::ErrorKind::NotConnected.into()) }
+//! # }
+//! use std::time::Duration;
+//! use tunein::cache::CachingTransport;
+//!
+//! let transport = CachingTransport::new(Http)
+//!     .ttl("Browse.ashx", Duration::from_secs(24 * 60 * 60))
+//!     .capacity(500)
+//!     .directory("/var/cache/tunein")?;
+//! let client = Client::new(transport);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+#[cfg(feature = "async")]
+use crate::async_client::{AsyncTransport, BoxFuture};
+use crate::client::{Response, Transport};
+use crate::common::{Document, Head};
+use crate::error::{Error, ErrorKind};
+use crate::event::Event;
+use crate::policy::is_transient;
+use crate::reader::{Reader, ReaderConfig};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+#[cfg(feature = "async")]
+use tokio::io::AsyncReadExt;
+use url::Url;
+
+/// TTL of endpoints without one of their own.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Number of responses kept in memory when no other capacity is set.
+pub const DEFAULT_CAPACITY: usize = 100;
+
+/// Value of the `Warning` header of a response served from the cache after
+/// a failed request.
+pub const STALE_WARNING: &str = "110 - \"Response is Stale\"";
+
+/// Transport caching the responses of another one, see the
+/// [module documentation](self).
+pub struct CachingTransport<T> {
+    inner: T,
+    default_ttl: Duration,
+    ttls: HashMap<String, Duration>,
+    capacity: usize,
+    directory: Option<PathBuf>,
+    memory: Mutex<Memory>,
+}
+
+#[derive(Default)]
+struct Memory {
+    entries: HashMap<String, (Entry, u64)>,
+    /// Incremented on each use of an entry, the least recent one is evicted.
+    tick: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    body: Vec<u8>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    stored: SystemTime,
+}
+
+/// What to do with a request, decided before sending it.
+enum Lookup {
+    Fresh(Response<Cursor<Vec<u8>>>),
+    /// Entry to revalidate, with the conditional request headers.
+    Stale(Vec<(String, String)>),
+    Miss,
+}
+
+impl<T> CachingTransport<T> {
+    /// Caches responses of `inner` in memory only.
+    ///
+    /// Playlists of `Tune.ashx` have a TTL of zero, since their stream URLs
+    /// may be short-lived. They're still kept for failed requests.
+    pub fn new(inner: T) -> CachingTransport<T> {
+        CachingTransport {
+            inner,
+            default_ttl: DEFAULT_TTL,
+            ttls: vec![("tune.ashx".to_string(), Duration::from_secs(0))]
+                .into_iter()
+                .collect(),
+            capacity: DEFAULT_CAPACITY,
+            directory: None,
+            memory: Mutex::default(),
+        }
+    }
+
+    /// Sets the TTL of `endpoint`, the last segment of the URL path such as
+    /// `Browse.ashx`, compared case-insensitively.
+    pub fn ttl(mut self, endpoint: &str, ttl: Duration) -> CachingTransport<T> {
+        self.ttls.insert(endpoint.to_ascii_lowercase(), ttl);
+        self
+    }
+
+    /// Sets the TTL of endpoints without one of their own, [`DEFAULT_TTL`] by default.
+    pub fn default_ttl(mut self, ttl: Duration) -> CachingTransport<T> {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Keeps at most `capacity` responses in memory, [`DEFAULT_CAPACITY`] by default.
+    pub fn capacity(mut self, capacity: usize) -> CachingTransport<T> {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Also stores the response bodies in `directory`, created if missing,
+    /// so that they survive a restart.
+    ///
+    /// Writing to the directory is best effort: a failure to store a
+    /// response doesn't fail the request.
+    pub fn directory<P: Into<PathBuf>>(mut self, directory: P) -> io::Result<CachingTransport<T>> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        self.directory = Some(directory);
+        Ok(self)
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Drops all responses, from memory and from the directory.
+    pub fn clear(&self) -> io::Result<()> {
+        self.memory.lock().unwrap().entries.clear();
+        if let Some(directory) = &self.directory {
+            for file in fs::read_dir(directory)? {
+                let path = file?.path();
+                if is_cache_file(&path) {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn ttl_of(&self, url: &Url) -> Duration {
+        let endpoint = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        self.ttls
+            .get(&endpoint)
+            .copied()
+            .unwrap_or(self.default_ttl)
+    }
+
+    fn lookup(&self, key: &str, url: &Url) -> Lookup {
+        let entry = match self.entry(key) {
+            Some(entry) => entry,
+            None => return Lookup::Miss,
+        };
+        let age = entry.stored.elapsed().unwrap_or_default();
+        if age < self.ttl_of(url) {
+            return Lookup::Fresh(entry.response(None));
+        }
+        let mut headers = vec![];
+        if let Some(etag) = entry.etag {
+            headers.push(("If-None-Match".to_string(), etag));
+        }
+        if let Some(last_modified) = entry.last_modified {
+            headers.push(("If-Modified-Since".to_string(), last_modified));
+        }
+        Lookup::Stale(headers)
+    }
+
+    /// Handles the response of the inner transport, with its body read.
+    fn complete(
+        &self,
+        key: &str,
+        response: io::Result<Response<Vec<u8>>>,
+    ) -> io::Result<Response<Cursor<Vec<u8>>>> {
+        match response {
+            Ok(response) if response.status == 304 => match self.entry(key) {
+                Some(mut entry) => {
+                    entry.stored = SystemTime::now();
+                    self.store(key, entry.clone());
+                    Ok(entry.response(None))
+                }
+                None => Ok(into_cursor(response)),
+            },
+            Ok(response) => match response_error(&response) {
+                None => {
+                    self.store(key, Entry::new(&response));
+                    Ok(into_cursor(response))
+                }
+                Some(error) if is_transient(&error) => self.stale(key, Ok(response)),
+                Some(_) => Ok(into_cursor(response)),
+            },
+            Err(error) => self.stale(key, Err(error)),
+        }
+    }
+
+    /// Last good response for `key` in place of a failed `response`, which
+    /// is returned if there's none.
+    fn stale(
+        &self,
+        key: &str,
+        response: io::Result<Response<Vec<u8>>>,
+    ) -> io::Result<Response<Cursor<Vec<u8>>>> {
+        match self.entry(key) {
+            Some(entry) => Ok(entry.response(Some(STALE_WARNING))),
+            None => response.map(into_cursor),
+        }
+    }
+
+    /// Entry from memory, or else from the directory.
+    fn entry(&self, key: &str) -> Option<Entry> {
+        let mut memory = self.memory.lock().unwrap();
+        memory.tick += 1;
+        let tick = memory.tick;
+        if let Some((entry, used)) = memory.entries.get_mut(key) {
+            *used = tick;
+            return Some(entry.clone());
+        }
+        drop(memory);
+
+        let entry = Entry::load(self.directory.as_ref()?, key)?;
+        self.remember(key, entry.clone());
+        Some(entry)
+    }
+
+    fn store(&self, key: &str, entry: Entry) {
+        if let Some(directory) = &self.directory {
+            let _ = entry.save(directory, key);
+        }
+        self.remember(key, entry);
+    }
+
+    fn remember(&self, key: &str, entry: Entry) {
+        let mut memory = self.memory.lock().unwrap();
+        memory.tick += 1;
+        let tick = memory.tick;
+        memory.entries.insert(key.to_string(), (entry, tick));
+        if memory.entries.len() > self.capacity {
+            let oldest = memory
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                memory.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl<T: Transport> Transport for CachingTransport<T> {
+    type Body = Cursor<Vec<u8>>;
+
+    fn get(&self, url: &Url) -> io::Result<Response<Self::Body>> {
+        self.get_with_headers(url, &[])
+    }
+
+    fn get_with_headers(
+        &self,
+        url: &Url,
+        headers: &[(String, String)],
+    ) -> io::Result<Response<Self::Body>> {
+        let key = normalize(url);
+        let mut headers = headers.to_vec();
+        match self.lookup(&key, url) {
+            Lookup::Fresh(response) => return Ok(response),
+            Lookup::Stale(conditions) => headers.extend(conditions),
+            Lookup::Miss => {}
+        }
+        let response = self
+            .inner
+            .get_with_headers(url, &headers)
+            .and_then(|mut response| {
+                let mut body = vec![];
+                response.body.read_to_end(&mut body)?;
+                Ok(with_body(response, body))
+            });
+        self.complete(&key, response)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + Sync> AsyncTransport for CachingTransport<T> {
+    type Body = Cursor<Vec<u8>>;
+
+    fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        self.get_with_headers(url, &[])
+    }
+
+    fn get_with_headers<'a>(
+        &'a self,
+        url: &'a Url,
+        headers: &'a [(String, String)],
+    ) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        Box::pin(async move {
+            let key = normalize(url);
+            let mut headers = headers.to_vec();
+            match self.lookup(&key, url) {
+                Lookup::Fresh(response) => return Ok(response),
+                Lookup::Stale(conditions) => headers.extend(conditions),
+                Lookup::Miss => {}
+            }
+            let response = match self.inner.get_with_headers(url, &headers).await {
+                Ok(mut response) => {
+                    let mut body = vec![];
+                    match response.body.read_to_end(&mut body).await {
+                        Ok(_) => Ok(with_body(response, body)),
+                        Err(error) => Err(error),
+                    }
+                }
+                Err(error) => Err(error),
+            };
+            self.complete(&key, response)
+        })
+    }
+}
+
+impl Entry {
+    fn new(response: &Response<Vec<u8>>) -> Entry {
+        Entry {
+            body: response.body.clone(),
+            etag: response.header("ETag").map(str::to_string),
+            last_modified: response.header("Last-Modified").map(str::to_string),
+            stored: SystemTime::now(),
+        }
+    }
+
+    fn response(self, warning: Option<&str>) -> Response<Cursor<Vec<u8>>> {
+        let mut headers = vec![];
+        if let Some(etag) = self.etag {
+            headers.push(("ETag".to_string(), etag));
+        }
+        if let Some(last_modified) = self.last_modified {
+            headers.push(("Last-Modified".to_string(), last_modified));
+        }
+        if let Some(warning) = warning {
+            headers.push(("Warning".to_string(), warning.to_string()));
+        }
+        Response {
+            status: 200,
+            headers,
+            body: Cursor::new(self.body),
+        }
+    }
+
+    /// Reads the body file and the metadata file stored next to it.
+    fn load(directory: &Path, key: &str) -> Option<Entry> {
+        let (body_path, meta_path) = paths(directory, key);
+        let meta = fs::read_to_string(meta_path).ok()?;
+        let mut entry = Entry {
+            body: vec![],
+            etag: None,
+            last_modified: None,
+            stored: UNIX_EPOCH,
+        };
+        let mut stored_key = None;
+        for line in meta.lines() {
+            match line.split_once(' ') {
+                Some(("url", value)) => stored_key = Some(value),
+                Some(("etag", value)) => entry.etag = Some(value.to_string()),
+                Some(("last-modified", value)) => entry.last_modified = Some(value.to_string()),
+                Some(("stored", value)) => {
+                    entry.stored = UNIX_EPOCH + Duration::from_secs(value.parse().ok()?)
+                }
+                _ => {}
+            }
+        }
+        // Guards against a collision of the file name hashes.
+        if stored_key != Some(key) {
+            return None;
+        }
+        entry.body = fs::read(body_path).ok()?;
+        Some(entry)
+    }
+
+    fn save(&self, directory: &Path, key: &str) -> io::Result<()> {
+        let (body_path, meta_path) = paths(directory, key);
+        let mut meta = format!("url {}\n", key);
+        if let Some(etag) = &self.etag {
+            meta += &format!("etag {}\n", etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            meta += &format!("last-modified {}\n", last_modified);
+        }
+        let stored = self.stored.duration_since(UNIX_EPOCH).unwrap_or_default();
+        meta += &format!("stored {}\n", stored.as_secs());
+        fs::write(body_path, &self.body)?;
+        fs::write(meta_path, meta)
+    }
+}
+
+/// Cache key of `url`: without fragment and with the query pairs sorted, so
+/// that the order of request parameters doesn't matter.
+fn normalize(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    let mut pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        pairs.sort();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url.to_string()
+}
+
+/// Paths of the body and metadata files of `key`, named after its FNV-1a hash.
+fn paths(directory: &Path, key: &str) -> (PathBuf, PathBuf) {
+    let hash = key.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    (
+        directory.join(format!("{:016x}.body", hash)),
+        directory.join(format!("{:016x}.meta", hash)),
+    )
+}
+
+fn is_cache_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("body") | Some("meta")
+    )
+}
+
+/// Error of a response which isn't cached: the fault its body reports, or
+/// else its status if it's unsuccessful.
+fn response_error(response: &Response<Vec<u8>>) -> Option<Error> {
+    match fault(&response.body) {
+        Some(fault) => Some(fault),
+        None if response.is_success() => None,
+        None => Some(Error::new(ErrorKind::HttpStatus {
+            status: response.status,
+        })),
+    }
+}
+
+/// Fault reported by `body` if it's an OPML document, read up to the end
+/// of its head only.
+fn fault(body: &[u8]) -> Option<Error> {
+    let mut head = Head::default();
+    let mut reader = Reader::with_config(body, ReaderConfig::lenient());
+    loop {
+        match reader.next().ok()? {
+            Event::Status(status) => head.status = status,
+            Event::Fault(fault) => head.fault = Some(fault),
+            Event::FaultCode(code) => head.fault_code = Some(code),
+            Event::EndHead | Event::StartBody | Event::EndDocument => break,
+            _ => {}
+        }
+    }
+    let document = Document {
+        head,
+        ..Default::default()
+    };
+    document.into_result().err()
+}
+
+fn with_body<B, C>(response: Response<B>, body: C) -> Response<C> {
+    Response {
+        status: response.status,
+        headers: response.headers,
+        body,
+    }
+}
+
+fn into_cursor(response: Response<Vec<u8>>) -> Response<Cursor<Vec<u8>>> {
+    Response {
+        status: response.status,
+        headers: response.headers,
+        body: Cursor::new(response.body),
+    }
+}
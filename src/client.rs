@@ -0,0 +1,256 @@
+use crate::common::{Audio, Document};
+use crate::error::{Error, ErrorKind};
+use crate::guide_id::GuideId;
+use crate::paging::Pages;
+use crate::policy::{exceeds, timeout_error, Governor, Outcome, Policy, Step};
+use crate::read::read;
+use crate::request::{check_base, Render, Request};
+use crate::tune::{audio_url, parse_response, Stream};
+use std::io::{self, Read};
+use url::Url;
+
+/// HTTP response as returned by a [`Transport`].
+#[derive(Debug)]
+pub struct Response<B> {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: B,
+}
+
+impl<B> Response<B> {
+    /// Value of the first header called `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Performs HTTP GET requests on behalf of a [`Client`].
+///
+/// Implement it on top of any HTTP library, or serve canned responses in tests.
+pub trait Transport {
+    type Body: Read;
+
+    fn get(&self, url: &Url) -> io::Result<Response<Self::Body>>;
+
+    /// Sends a GET request with additional request headers, such as the
+    /// conditions of a revalidation. Transports which can't send headers
+    /// may ignore them, as the default implementation does.
+    fn get_with_headers(
+        &self,
+        url: &Url,
+        _headers: &[(String, String)],
+    ) -> io::Result<Response<Self::Body>> {
+        self.get(url)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    type Body = T::Body;
+
+    fn get(&self, url: &Url) -> io::Result<Response<Self::Body>> {
+        (**self).get(url)
+    }
+
+    fn get_with_headers(
+        &self,
+        url: &Url,
+        headers: &[(String, String)],
+    ) -> io::Result<Response<Self::Body>> {
+        (**self).get_with_headers(url, headers)
+    }
+}
+
+/// Blocking TuneIn client fetching and parsing documents through a [`Transport`].
+///
+/// ```
+/// use std::io;
+/// use tunein::client::{Client, Response, Transport};
+/// use tunein::Url;
+///
+/// struct Offline;
+///
+/// impl Transport for Offline {
+///     type Body = &'static [u8];
+///
+///     fn get(&self, _url: &Url) -> io::Result<Response<Self::Body>> {
+///         Ok(Response {
+///             status: 200,
+///             headers: vec![],
+///             body: br#"<opml version="1"><head><title>Browse</title></head></opml>"#,
+///         })
+///     }
+/// }
+///
+/// let document = Client::new(Offline).browse()?;
+/// assert_eq!(document.head.title, "Browse");
+/// # Ok::<(), tunein::Error>(())
+/// ```
+pub struct Client<T> {
+    transport: T,
+    pub(crate) base: Option<Url>,
+    governor: Governor,
+}
+
+impl<T: Transport> Client<T> {
+    pub fn new(transport: T) -> Client<T> {
+        Client {
+            transport,
+            base: None,
+            governor: Governor::new(Policy::default()),
+        }
+    }
+
+    /// Sends all requests to `base` instead of the TuneIn server.
+    ///
+    /// Fails for URLs which can't be a base, such as `mailto:` URLs.
+    pub fn with_base(transport: T, base: Url) -> Result<Client<T>, Error> {
+        Ok(Client {
+            transport,
+            base: Some(check_base(base)?),
+            governor: Governor::new(Policy::default()),
+        })
+    }
+
+    /// Sends requests following `policy`, see [`policy`](crate::policy).
+    pub fn with_policy(mut self, policy: Policy) -> Client<T> {
+        self.governor = Governor::new(policy);
+        self
+    }
+
+    pub fn policy(&self) -> &Policy {
+        self.governor.policy()
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Root of the directory.
+    pub fn browse(&self) -> Result<Document, Error> {
+        self.get(&Request::browse())
+    }
+
+    pub fn browse_category(&self, category: &str) -> Result<Document, Error> {
+        self.get(&Request::browse().category(category))
+    }
+
+    pub fn browse_id(&self, id: &GuideId) -> Result<Document, Error> {
+        self.get(&Request::browse().id(id))
+    }
+
+    pub fn search(&self, query: &str) -> Result<Document, Error> {
+        self.get(&Request::search(query))
+    }
+
+    pub fn describe(&self, id: &GuideId) -> Result<Document, Error> {
+        self.get(&Request::describe(id))
+    }
+
+    /// Streams of a station or topic, rendered as OPML audio outlines.
+    pub fn tune(&self, id: &GuideId) -> Result<Document, Error> {
+        self.get(&Request::tune(id).render(Render::Xml))
+    }
+
+    pub fn get(&self, request: &Request) -> Result<Document, Error> {
+        self.fetch(&request_url(&self.base, request))
+    }
+
+    /// Fetches a document by URL, e.g. one found in a [`Link`](crate::Link).
+    pub fn fetch(&self, url: &Url) -> Result<Document, Error> {
+        self.send(url, || {
+            let response = check_response(self.transport.get(url))?;
+            read(response.body)
+        })
+    }
+
+    /// Pages of a listing, following its continuation links.
+    pub fn pages(&self, request: &Request) -> Pages<'_, T> {
+        Pages::new(self, request_url(&self.base, request))
+    }
+
+    /// Pages of a listing starting at `url`, following its continuation links.
+    pub fn fetch_pages(&self, url: &Url) -> Pages<'_, T> {
+        Pages::new(self, url.clone())
+    }
+
+    /// Fetches a `Tune.ashx` playlist and returns its streams in playlist order.
+    pub fn resolve(&self, url: &Url) -> Result<Vec<Stream>, Error> {
+        self.send(url, || {
+            let mut response = check_response(self.transport.get(url))?;
+            let mut content = Vec::new();
+            response
+                .body
+                .read_to_end(&mut content)
+                .map_err(|error| Error::new(ErrorKind::Io(error)))?;
+            parse_response(&content)
+        })
+    }
+
+    /// Resolves the URL of an audio outline, using its bitrate and format as hints.
+    pub fn resolve_audio(&self, audio: &Audio) -> Result<Vec<Stream>, Error> {
+        let streams = self.resolve(&audio_url(audio)?)?;
+        Ok(streams
+            .into_iter()
+            .map(|stream| stream.with_audio_hints(audio))
+            .collect())
+    }
+
+    /// Runs `attempt` with the rate limit, retries and timeout of the policy.
+    fn send<R, F>(&self, url: &Url, attempt: F) -> Result<R, Error>
+    where
+        F: Fn() -> Result<R, Error>,
+        Result<R, Error>: Outcome,
+    {
+        let deadline = self.governor.deadline();
+        let mut retry = 0;
+        loop {
+            let wait = self.governor.acquire(url);
+            if exceeds(deadline, wait) {
+                return Err(timeout_error());
+            }
+            std::thread::sleep(wait);
+            let outcome = attempt();
+            match self.governor.next(&outcome, retry, deadline) {
+                Step::Retry(delay) => std::thread::sleep(delay),
+                Step::Stop => return outcome,
+            }
+            retry += 1;
+        }
+    }
+}
+
+pub(crate) fn request_url(base: &Option<Url>, request: &Request) -> Url {
+    match base {
+        Some(base) => request.url_at(base),
+        None => request.url(),
+    }
+}
+
+pub(crate) fn check_response<B: Read>(
+    response: io::Result<Response<B>>,
+) -> Result<Response<B>, Error> {
+    let mut response = response.map_err(|error| Error::new(ErrorKind::Io(error)))?;
+    if response.is_success() {
+        Ok(response)
+    } else {
+        let mut body = Vec::new();
+        let body = response.body.read_to_end(&mut body).map(|_| body);
+        Err(status_error(response.status, body.ok().as_deref()))
+    }
+}
+
+/// Error for an unsuccessful response: the fault its body reports if it's a
+/// TuneIn document with one, the HTTP status otherwise.
+pub(crate) fn status_error(status: u16, body: Option<&[u8]>) -> Error {
+    match body.map(|body| read(body).map(Document::into_result)) {
+        Some(Ok(Err(fault))) => fault,
+        _ => Error::new(ErrorKind::HttpStatus { status }),
+    }
+}
@@ -1,69 +1,448 @@
-#[derive(Debug, Default, PartialEq)]
+use crate::error::{Error, ErrorKind, Fault};
+use crate::guide_id::GuideId;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Document {
     pub version: Version,
     pub head: Head,
     pub outlines: Vec<Outline>,
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Version {
     pub major: u8,
     pub minor: u8,
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Head {
     pub title: String,
     pub status: Option<u32>,
+    /// Error message of a failed request.
+    pub fault: Option<String>,
+    /// Machine-readable error code of a failed request, e.g. `validation.invalidId`.
+    pub fault_code: Option<String>,
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Group {
     pub text: String,
     pub key: String,
+    pub attributes: Attributes,
     pub outlines: Vec<Outline>,
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Link {
     pub text: String,
     pub url: String,
     pub key: String,
-    pub guide_id: String,
+    pub guide_id: Option<GuideId>,
+    pub item: Option<Item>,
+    pub attributes: Attributes,
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Audio {
     pub text: String,
     pub subtext: String,
     pub url: String,
-    pub bitrate: u16,
-    pub reliability: u16,
-    pub format: Format,
-    pub item: String,
+    pub bitrate: Option<u16>,
+    pub reliability: Option<u16>,
+    pub formats: Vec<Format>,
+    pub item: Option<Item>,
     pub image: String,
-    pub guide_id: String,
-    pub genre_id: String,
-    pub now_playing_id: String,
-    pub preset_id: String,
+    pub guide_id: Option<GuideId>,
+    pub genre_id: Option<GuideId>,
+    pub now_playing_id: Option<GuideId>,
+    pub preset_id: Option<GuideId>,
+    pub attributes: Attributes,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct Text {
+    pub text: String,
+    pub attributes: Attributes,
+}
+
+/// Outline of a `Describe.ashx` response, wrapping the description of a
+/// station, program or topic.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct Object {
+    pub text: String,
+    pub detail: Option<Detail>,
+    pub attributes: Attributes,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "type", rename_all = "lowercase")
+)]
+pub enum Detail {
+    Station(StationDetail),
+    Program(ProgramDetail),
+    /// Any other element, e.g. `<topic>`, with its children as name/value pairs.
+    Other {
+        name: String,
+        fields: Attributes,
+    },
+}
+
+/// Contents of a `<station>` element.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct StationDetail {
+    pub guide_id: Option<GuideId>,
+    pub preset_id: Option<GuideId>,
+    pub name: String,
+    pub call_sign: String,
+    pub slogan: String,
+    pub description: String,
+    pub frequency: String,
+    pub band: String,
+    pub url: String,
+    pub logo: String,
+    pub location: String,
+    pub genre_id: Option<GuideId>,
+    pub genre_name: String,
+    pub region_id: Option<GuideId>,
+    pub language: String,
+    pub twitter_id: String,
+    pub is_available: bool,
+    pub is_music: bool,
+    pub has_song: bool,
+    pub has_schedule: bool,
+    pub has_topics: bool,
+    pub fields: Attributes,
+}
+
+/// Contents of a `<program>` element.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct ProgramDetail {
+    pub guide_id: Option<GuideId>,
+    pub name: String,
+    pub description: String,
+    pub hosts: String,
+    pub url: String,
+    pub logo: String,
+    pub location: String,
+    pub genre_id: Option<GuideId>,
+    pub genre_name: String,
+    pub language: String,
+    pub twitter_id: String,
+    pub is_available: bool,
+    pub has_topics: bool,
+    pub fields: Attributes,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "type", rename_all = "lowercase")
+)]
 pub enum Outline {
     Group(Group),
     Link(Link),
     Audio(Audio),
-    Text(String),
+    Text(Text),
+    Object(Object),
 }
 
-#[derive(Debug, PartialEq)]
+/// Outline attributes not recognised by the parser, in document order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Attributes(Vec<(String, String)>);
+
+/// Stream format, as listed in the `formats` attribute.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Format {
-    Unknown,
+    AAC,
+    AACPlus,
     MP3,
+    OGG,
+    WMA,
+    HLS,
+    FLAC,
+    Flash,
+    Real,
+    Other(String),
+}
+
+/// Kind of object an outline points to, as given by the `item` attribute.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Item {
+    Station,
+    Topic,
+    Show,
+    Program,
+    Song,
+    Artist,
+    Other(String),
+}
+
+impl Document {
+    /// Turns a fault response into an [`ErrorKind::Fault`] error.
+    ///
+    /// Statuses from 400 up are faults. Search results come with status 300,
+    /// so redirections count as success, as does a missing status.
+    pub fn into_result(self) -> Result<Document, Error> {
+        match self.head.status {
+            Some(status) if status >= 400 => Err(Error::new(ErrorKind::Fault(Fault {
+                status,
+                message: self.head.fault.unwrap_or_default(),
+                code: self.head.fault_code,
+            }))),
+            _ => Ok(self),
+        }
+    }
+}
+
+impl Outline {
+    /// Text shown for the outline.
+    pub fn text(&self) -> &str {
+        match self {
+            Outline::Group(group) => &group.text,
+            Outline::Link(link) => &link.text,
+            Outline::Audio(audio) => &audio.text,
+            Outline::Text(text) => &text.text,
+            Outline::Object(object) => &object.text,
+        }
+    }
+
+    /// Key of a group or link, empty for other outlines.
+    pub fn key(&self) -> &str {
+        match self {
+            Outline::Group(group) => &group.key,
+            Outline::Link(link) => &link.key,
+            _ => "",
+        }
+    }
+
+    /// Identifier of the linked object, or of the described station or program.
+    pub fn guide_id(&self) -> Option<&GuideId> {
+        match self {
+            Outline::Link(link) => link.guide_id.as_ref(),
+            Outline::Audio(audio) => audio.guide_id.as_ref(),
+            Outline::Object(object) => match &object.detail {
+                Some(Detail::Station(station)) => station.guide_id.as_ref(),
+                Some(Detail::Program(program)) => program.guide_id.as_ref(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Group {
+    /// Value of an attribute not recognised by the parser.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name)
+    }
+}
+
+impl Link {
+    /// Value of an attribute not recognised by the parser.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name)
+    }
+}
+
+impl Audio {
+    /// Value of an attribute not recognised by the parser.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name)
+    }
+}
+
+impl Text {
+    /// Value of an attribute not recognised by the parser.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name)
+    }
+}
+
+impl Object {
+    /// Value of an attribute not recognised by the parser.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name)
+    }
+}
+
+impl StationDetail {
+    /// Value of a child element not recognised by the parser.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name)
+    }
+}
+
+impl ProgramDetail {
+    /// Value of a child element not recognised by the parser.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name)
+    }
+}
+
+impl From<String> for Text {
+    fn from(text: String) -> Text {
+        Text {
+            text,
+            attributes: Attributes::default(),
+        }
+    }
+}
+
+impl From<&str> for Text {
+    fn from(text: &str) -> Text {
+        text.to_string().into()
+    }
+}
+
+impl Attributes {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of an attribute, keeping its position if it already exists.
+    pub fn insert<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        let name = name.into();
+        let value = value.into();
+        match self.0.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.0.push((name, value)),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let index = self.0.iter().position(|(n, _)| n == name)?;
+        Some(self.0.remove(index).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<N: Into<String>, V: Into<String>> std::iter::FromIterator<(N, V)> for Attributes {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Attributes {
+        let mut attributes = Attributes::default();
+        for (name, value) in iter {
+            attributes.insert(name, value);
+        }
+        attributes
+    }
+}
+
+impl Format {
+    /// Parses a comma-separated list of formats, skipping duplicates.
+    pub fn parse_list(value: &str) -> Vec<Format> {
+        let mut formats = Vec::new();
+        for format in value.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+            let format = format.parse().unwrap_or_else(|never| match never {});
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+        formats
+    }
+
+    /// Formats the list the way the `formats` attribute expects it.
+    pub fn join_list(formats: &[Format]) -> String {
+        formats
+            .iter()
+            .map(Format::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = std::convert::Infallible;
+
+    fn from_str(value: &str) -> Result<Format, Self::Err> {
+        Ok(match value.to_ascii_lowercase().as_str() {
+            "aac" => Format::AAC,
+            "aacp" | "aac+" => Format::AACPlus,
+            "mp3" => Format::MP3,
+            "ogg" => Format::OGG,
+            "wma" => Format::WMA,
+            "hls" => Format::HLS,
+            "flac" => Format::FLAC,
+            "flash" => Format::Flash,
+            "real" => Format::Real,
+            _ => Format::Other(value.to_string()),
+        })
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Format::AAC => "aac",
+            Format::AACPlus => "aacp",
+            Format::MP3 => "mp3",
+            Format::OGG => "ogg",
+            Format::WMA => "wma",
+            Format::HLS => "hls",
+            Format::FLAC => "flac",
+            Format::Flash => "flash",
+            Format::Real => "real",
+            Format::Other(format) => format,
+        })
+    }
+}
+
+impl std::str::FromStr for Item {
+    type Err = std::convert::Infallible;
+
+    fn from_str(value: &str) -> Result<Item, Self::Err> {
+        Ok(match value {
+            "station" => Item::Station,
+            "topic" => Item::Topic,
+            "show" => Item::Show,
+            "program" => Item::Program,
+            "song" => Item::Song,
+            "artist" => Item::Artist,
+            _ => Item::Other(value.to_string()),
+        })
+    }
 }
 
-impl Default for Format {
-    fn default() -> Format {
-        Format::Unknown
+impl std::fmt::Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Item::Station => "station",
+            Item::Topic => "topic",
+            Item::Show => "show",
+            Item::Program => "program",
+            Item::Song => "song",
+            Item::Artist => "artist",
+            Item::Other(item) => item,
+        })
     }
 }
@@ -0,0 +1,349 @@
+//! Crawler mirroring the directory by following link outlines.
+//!
+//! The crawler fetches documents level by level, up to a maximum depth, and
+//! replaces each followed [`Link`] by a [`Group`] holding the outlines of the
+//! document it points to. Listings split into pages are fetched whole.
+//!
+//! ```no_run
+//! # use tunein::client::{Client, Response, Transport};
+//! # struct Http;
+//! # impl Transport for Http {
+//! #     type Body = &'static [u8];
+//! #     fn get(&self, _: &tunein::Url) -> std::io::Result<Response<&'static [u8]>> { Err(std::io::ErrorKind::NotConnected.into()) }
+//! # }
+//! use tunein::crawl::{Crawler, Progress};
+//! use tunein::request::Request;
+//!
+//! let client = Client::new(Http);
+//! let crawl = Crawler::new(&client)
+//!     .max_depth(2)
+//!     .concurrency(4)
+//!     .exclude(|link| link.key == "local")
+//!     .on_progress(|progress| {
+//!         if let Progress::Fetched { url, .. } = progress {
+//!             eprintln!("{}", url);
+//!         }
+//!     })
+//!     .crawl(&Request::browse())?;
+//! println!("{} stations", crawl.document.audios().count());
+//! # Ok::<(), tunein::Error>(())
+//! ```
+
+use crate::client::{request_url, Client, Transport};
+use crate::common::{Document, Group, Link, Outline};
+use crate::error::Error;
+use crate::request::Request;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{mpsc, Mutex};
+use url::Url;
+
+/// Builder and runner of a crawl, see the [module documentation](self).
+pub struct Crawler<'a, T> {
+    client: &'a Client<T>,
+    max_depth: usize,
+    concurrency: usize,
+    include: Option<LinkPredicate<'a>>,
+    exclude: Option<LinkPredicate<'a>>,
+    progress: Option<ProgressCallback<'a>>,
+}
+
+type LinkPredicate<'a> = Box<dyn Fn(&Link) -> bool + 'a>;
+type ProgressCallback<'a> = Box<dyn FnMut(Progress) + 'a>;
+
+/// Event passed to the [progress callback](Crawler::on_progress).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Progress<'a> {
+    Fetched {
+        url: &'a Url,
+        /// Number of links followed from the start document.
+        depth: usize,
+        fetched: usize,
+        /// Documents queued at this depth and not fetched yet.
+        pending: usize,
+    },
+    Failed {
+        url: &'a Url,
+        depth: usize,
+        error: &'a Error,
+    },
+}
+
+/// Result of a crawl.
+#[derive(Debug)]
+pub struct Crawl {
+    /// Start document with the followed links replaced by groups.
+    pub document: Document,
+    /// Links which couldn't be fetched or reported a fault. They are left in
+    /// the document as links.
+    pub failures: Vec<Failure>,
+    /// Documents whose listing was cut at the
+    /// [page limit](crate::paging::DEFAULT_PAGE_LIMIT).
+    pub truncated: Vec<Url>,
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    pub url: Url,
+    pub error: Error,
+}
+
+/// Link to follow, located by the indices leading to it in the merged tree.
+struct Job {
+    url: Url,
+    path: Vec<usize>,
+}
+
+impl<'a, T: Transport + Sync> Crawler<'a, T> {
+    /// Creates a crawler following links to any depth, one at a time.
+    pub fn new(client: &'a Client<T>) -> Crawler<'a, T> {
+        Crawler {
+            client,
+            max_depth: usize::MAX,
+            concurrency: 1,
+            include: None,
+            exclude: None,
+            progress: None,
+        }
+    }
+
+    /// Follows links at most `max_depth` levels away from the start document.
+    pub fn max_depth(mut self, max_depth: usize) -> Crawler<'a, T> {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Fetches up to `concurrency` documents at the same time.
+    pub fn concurrency(mut self, concurrency: usize) -> Crawler<'a, T> {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Follows only the links for which `predicate` returns `true`.
+    pub fn include<P: Fn(&Link) -> bool + 'a>(mut self, predicate: P) -> Crawler<'a, T> {
+        self.include = Some(Box::new(predicate));
+        self
+    }
+
+    /// Doesn't follow the links for which `predicate` returns `true`.
+    pub fn exclude<P: Fn(&Link) -> bool + 'a>(mut self, predicate: P) -> Crawler<'a, T> {
+        self.exclude = Some(Box::new(predicate));
+        self
+    }
+
+    /// Calls `callback` after each fetched or failed document.
+    pub fn on_progress<F: FnMut(Progress) + 'a>(mut self, callback: F) -> Crawler<'a, T> {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    pub fn crawl(self, request: &Request) -> Result<Crawl, Error> {
+        let url = request_url(&self.client.base, request);
+        self.crawl_url(&url)
+    }
+
+    /// Crawls starting from the document at `url`, which must be fetched
+    /// successfully.
+    pub fn crawl_url(mut self, url: &Url) -> Result<Crawl, Error> {
+        let (mut document, truncated_start) = fetch(self.client, url)?;
+        let mut truncated = vec![];
+        if truncated_start {
+            truncated.push(url.clone());
+        }
+        self.report(Progress::Fetched {
+            url,
+            depth: 0,
+            fetched: 1,
+            pending: 0,
+        });
+
+        let mut seen = HashSet::new();
+        seen.insert(url.to_string());
+        let mut fetched = 1;
+        let mut failures = vec![];
+        let mut jobs = vec![];
+        if self.max_depth > 0 {
+            self.collect_jobs(url, &document.outlines, &[], &mut seen, &mut jobs);
+        }
+
+        let mut depth = 1;
+        while !jobs.is_empty() {
+            let results = self.fetch_level(&jobs, depth, &mut fetched);
+            let mut next_jobs = vec![];
+            for (job, result) in jobs.into_iter().zip(results) {
+                match result {
+                    Ok((page, is_truncated)) => {
+                        if is_truncated {
+                            truncated.push(job.url.clone());
+                        }
+                        let outline = outline_at(&mut document.outlines, &job.path);
+                        let group = expand(outline, page);
+                        if depth < self.max_depth {
+                            let outlines = &group.outlines;
+                            self.collect_jobs(
+                                &job.url,
+                                outlines,
+                                &job.path,
+                                &mut seen,
+                                &mut next_jobs,
+                            );
+                        }
+                    }
+                    Err(error) => failures.push(Failure {
+                        url: job.url,
+                        error,
+                    }),
+                }
+            }
+            jobs = next_jobs;
+            depth += 1;
+        }
+
+        Ok(Crawl {
+            document,
+            failures,
+            truncated,
+        })
+    }
+
+    /// Queues the links to follow among `outlines`, found in the document at `base`.
+    fn collect_jobs(
+        &self,
+        base: &Url,
+        outlines: &[Outline],
+        path: &[usize],
+        seen: &mut HashSet<String>,
+        jobs: &mut Vec<Job>,
+    ) {
+        for (index, outline) in outlines.iter().enumerate() {
+            let path = [path, &[index]].concat();
+            match outline {
+                Outline::Group(group) => {
+                    self.collect_jobs(base, &group.outlines, &path, seen, jobs)
+                }
+                Outline::Link(link) if self.follows(link) => {
+                    if let Ok(url) = base.join(&link.url) {
+                        if seen.insert(url.to_string()) {
+                            jobs.push(Job { url, path });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn follows(&self, link: &Link) -> bool {
+        let included = self.include.as_ref().map_or(true, |include| include(link));
+        let excluded = self.exclude.as_ref().map_or(false, |exclude| exclude(link));
+        included && !excluded
+    }
+
+    /// Fetches the documents of one level on worker threads, returning the
+    /// results in the order of `jobs`.
+    fn fetch_level(
+        &mut self,
+        jobs: &[Job],
+        depth: usize,
+        fetched: &mut usize,
+    ) -> Vec<Result<(Document, bool), Error>> {
+        let queue = Mutex::new(jobs.iter().enumerate().collect::<VecDeque<_>>());
+        let (sender, receiver) = mpsc::channel();
+        let client = self.client;
+        let mut results: Vec<Option<Result<(Document, bool), Error>>> =
+            jobs.iter().map(|_| None).collect();
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.concurrency.min(jobs.len()) {
+                let sender = sender.clone();
+                let queue = &queue;
+                scope.spawn(move || loop {
+                    let next = queue.lock().unwrap().pop_front();
+                    match next {
+                        Some((index, job)) => {
+                            let result = fetch(client, &job.url);
+                            if sender.send((index, result)).is_err() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    }
+                });
+            }
+            drop(sender);
+
+            for (count, (index, result)) in receiver.iter().enumerate() {
+                let url = &jobs[index].url;
+                match &result {
+                    Ok(_) => {
+                        *fetched += 1;
+                        self.report(Progress::Fetched {
+                            url,
+                            depth,
+                            fetched: *fetched,
+                            pending: jobs.len() - count - 1,
+                        });
+                    }
+                    Err(error) => self.report(Progress::Failed { url, depth, error }),
+                }
+                results[index] = Some(result);
+            }
+        });
+
+        results
+            .into_iter()
+            .map(|result| result.expect("Every job sends a result"))
+            .collect()
+    }
+
+    fn report(&mut self, progress: Progress) {
+        if let Some(callback) = &mut self.progress {
+            callback(progress);
+        }
+    }
+}
+
+/// Fetches all pages of the document at `url`, and whether they were truncated.
+fn fetch<T: Transport>(client: &Client<T>, url: &Url) -> Result<(Document, bool), Error> {
+    let mut pages = client.fetch_pages(url);
+    let document = pages.merge()?;
+    Ok((document, pages.truncated()))
+}
+
+fn outline_at<'a>(outlines: &'a mut [Outline], path: &[usize]) -> &'a mut Outline {
+    let (first, rest) = path.split_first().expect("Path is not empty");
+    let outline = &mut outlines[*first];
+    if rest.is_empty() {
+        return outline;
+    }
+    match outline {
+        Outline::Group(group) => outline_at(&mut group.outlines, rest),
+        _ => unreachable!("Path leads through groups"),
+    }
+}
+
+/// Replaces a link by a group with the outlines of the page it points to.
+fn expand(outline: &mut Outline, page: Document) -> &Group {
+    let link = match std::mem::replace(outline, Outline::Text(Default::default())) {
+        Outline::Link(link) => link,
+        _ => unreachable!("Crawled outline is not a link"),
+    };
+    let mut attributes = link.attributes;
+    attributes.insert("URL", link.url);
+    if let Some(guide_id) = link.guide_id {
+        attributes.insert("guide_id", guide_id.to_string());
+    }
+    if let Some(item) = link.item {
+        attributes.insert("item", item.to_string());
+    }
+    *outline = Outline::Group(Group {
+        text: link.text,
+        key: link.key,
+        attributes,
+        outlines: page.outlines,
+    });
+    match outline {
+        Outline::Group(group) => group,
+        _ => unreachable!(),
+    }
+}
@@ -1,32 +1,284 @@
+use std::fmt;
+use xml::common::Position as _;
+
+/// Error returned when reading or writing a document.
+///
+/// Besides the [`ErrorKind`], parse errors carry the [`Position`] in the
+/// source at which the problem was found.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    position: Option<Position>,
+}
+
 #[derive(Debug)]
-pub enum Error {
+#[non_exhaustive]
+pub enum ErrorKind {
     XmlError(xml::reader::Error),
-    UnexpectedElement,
+    XmlWriterError(xml::writer::Error),
+    #[cfg(feature = "json")]
+    JsonError(serde_json::Error),
+    UnexpectedElement {
+        name: String,
+    },
     MissingVersionAttr,
-    InvalidVersionFormat,
-    InvalidOutlineType,
-    InvalidBitrateFormat,
-    InvalidReliabilityFormat,
+    /// The document ended without an `<opml>` root element.
+    MissingOpmlElement,
+    InvalidVersionFormat {
+        value: String,
+    },
+    InvalidOutlineType {
+        value: String,
+    },
+    InvalidBitrateFormat {
+        value: String,
+    },
+    InvalidReliabilityFormat {
+        value: String,
+    },
+    InvalidGuideId {
+        attribute: String,
+        value: String,
+    },
+    Io(std::io::Error),
+    HttpStatus {
+        status: u16,
+    },
+    InvalidUrl {
+        value: String,
+    },
+    /// The URL given as base of the requests can't have relative URLs
+    /// resolved against it, e.g. a `mailto:` URL.
+    InvalidBaseUrl {
+        value: String,
+    },
+    EmptyPlaylist,
+    Fault(Fault),
+    /// The request didn't complete within the timeout of the client policy.
+    Timeout,
+}
+
+/// Error reported by TuneIn in the head of a response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fault {
+    pub status: u32,
+    pub message: String,
+    pub code: Option<String>,
+}
+
+/// Broad category of a [`Fault`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FaultKind {
+    /// The requested station, program or category doesn't exist.
+    InvalidId,
+    InvalidRequest,
+    RateLimited,
+    ServerError,
+    Other,
+}
+
+/// Line and column in the source document, both counted from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: u64,
+    pub column: u64,
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            position: None,
+        }
+    }
+
+    pub(crate) fn at(self, position: Position) -> Error {
+        Error {
+            position: Some(position),
+            ..self
+        }
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Position in the source document, if the error comes from parsing.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    /// Name of the element which failed to parse.
+    pub fn element(&self) -> Option<&str> {
+        match &self.kind {
+            ErrorKind::UnexpectedElement { name } => Some(name),
+            ErrorKind::MissingVersionAttr
+            | ErrorKind::MissingOpmlElement
+            | ErrorKind::InvalidVersionFormat { .. } => Some("opml"),
+            ErrorKind::InvalidOutlineType { .. }
+            | ErrorKind::InvalidBitrateFormat { .. }
+            | ErrorKind::InvalidReliabilityFormat { .. }
+            | ErrorKind::InvalidGuideId { .. }
+            | ErrorKind::InvalidUrl { .. } => Some("outline"),
+            _ => None,
+        }
+    }
+
+    /// Name of the attribute which failed to parse.
+    pub fn attribute(&self) -> Option<&str> {
+        match &self.kind {
+            ErrorKind::MissingVersionAttr | ErrorKind::InvalidVersionFormat { .. } => {
+                Some("version")
+            }
+            ErrorKind::InvalidOutlineType { .. } => Some("type"),
+            ErrorKind::InvalidBitrateFormat { .. } => Some("bitrate"),
+            ErrorKind::InvalidReliabilityFormat { .. } => Some("reliability"),
+            ErrorKind::InvalidGuideId { attribute, .. } => Some(attribute),
+            ErrorKind::InvalidUrl { .. } => Some("URL"),
+            _ => None,
+        }
+    }
+
+    /// Raw attribute value which failed to parse.
+    pub fn value(&self) -> Option<&str> {
+        match &self.kind {
+            ErrorKind::InvalidVersionFormat { value }
+            | ErrorKind::InvalidOutlineType { value }
+            | ErrorKind::InvalidBitrateFormat { value }
+            | ErrorKind::InvalidReliabilityFormat { value }
+            | ErrorKind::InvalidGuideId { value, .. }
+            | ErrorKind::InvalidUrl { value }
+            | ErrorKind::InvalidBaseUrl { value } => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl Fault {
+    pub fn kind(&self) -> FaultKind {
+        let code = self
+            .code
+            .as_deref()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if self.status == 429 || code.contains("ratelimit") || code.contains("rate.limit") {
+            FaultKind::RateLimited
+        } else if self.status == 404 || code.contains("invalidid") || code.contains("notfound") {
+            FaultKind::InvalidId
+        } else if (400..500).contains(&self.status) {
+            FaultKind::InvalidRequest
+        } else if self.status >= 500 {
+            FaultKind::ServerError
+        } else {
+            FaultKind::Other
+        }
+    }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::XmlError(e) => Some(e),
+            ErrorKind::XmlWriterError(e) => Some(e),
+            #[cfg(feature = "json")]
+            ErrorKind::JsonError(e) => Some(e),
+            ErrorKind::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error::new(kind)
+    }
+}
 
 impl From<xml::reader::Error> for Error {
     fn from(error: xml::reader::Error) -> Self {
-        Error::XmlError(error)
+        let position = error.position().into();
+        Error::new(ErrorKind::XmlError(error)).at(position)
     }
 }
 
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            Error::XmlError(e) => write!(f, "{}", e),
-            Error::UnexpectedElement => write!(f, "Unexpected element"),
-            Error::MissingVersionAttr => write!(f, "Missing version attribute"),
-            Error::InvalidVersionFormat => write!(f, "Invalid version format"),
-            Error::InvalidOutlineType => write!(f, "Invalid outline type"),
-            Error::InvalidBitrateFormat => write!(f, "Invalid bitrate format"),
-            Error::InvalidReliabilityFormat => write!(f, "Invalid reliability format"),
+impl From<xml::writer::Error> for Error {
+    fn from(error: xml::writer::Error) -> Self {
+        Error::new(ErrorKind::XmlWriterError(error))
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::new(ErrorKind::JsonError(error))
+    }
+}
+
+impl From<xml::common::TextPosition> for Position {
+    fn from(position: xml::common::TextPosition) -> Self {
+        Position {
+            line: position.row + 1,
+            column: position.column + 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::XmlError(e) => return write!(f, "{}", e),
+            ErrorKind::XmlWriterError(e) => write!(f, "{}", e),
+            #[cfg(feature = "json")]
+            ErrorKind::JsonError(e) => write!(f, "{}", e),
+            ErrorKind::UnexpectedElement { name } => write!(f, "Unexpected element <{}>", name),
+            ErrorKind::MissingVersionAttr => write!(f, "Missing version attribute"),
+            ErrorKind::MissingOpmlElement => write!(f, "Missing <opml> element"),
+            ErrorKind::InvalidVersionFormat { value } => {
+                write!(f, "Invalid version format \"{}\"", value)
+            }
+            ErrorKind::InvalidOutlineType { value } => {
+                write!(f, "Invalid outline type \"{}\"", value)
+            }
+            ErrorKind::InvalidBitrateFormat { value } => {
+                write!(f, "Invalid bitrate format \"{}\"", value)
+            }
+            ErrorKind::InvalidReliabilityFormat { value } => {
+                write!(f, "Invalid reliability format \"{}\"", value)
+            }
+            ErrorKind::InvalidGuideId { attribute, value } => {
+                write!(f, "Invalid {} \"{}\"", attribute, value)
+            }
+            ErrorKind::Io(e) => write!(f, "{}", e),
+            ErrorKind::HttpStatus { status } => write!(f, "HTTP status {}", status),
+            ErrorKind::InvalidUrl { value } => write!(f, "Invalid URL \"{}\"", value),
+            ErrorKind::InvalidBaseUrl { value } => write!(f, "Invalid base URL \"{}\"", value),
+            ErrorKind::EmptyPlaylist => write!(f, "Playlist contains no streams"),
+            ErrorKind::Fault(fault) => write!(f, "{}", fault),
+            ErrorKind::Timeout => write!(f, "Request timed out"),
+        }?;
+        if let Some(position) = self.position {
+            write!(f, " at {}", position)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fault {}", self.status)?;
+        if let Some(code) = &self.code {
+            write!(f, " ({})", code)?;
+        }
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
         }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
     }
 }
@@ -1,8 +1,13 @@
-use crate::common::{Audio, Group, Link, Outline};
+use crate::common::{Attributes, Audio, Group, Link, Object, Outline, Text, Version};
 
+/// A single event produced by [`Reader`](crate::Reader).
+///
+/// Events arrive in document order. Every `StartOutline` is matched by an
+/// `EndOutline`, with the events of nested outlines in between.
 #[derive(Debug, PartialEq)]
+#[non_exhaustive]
 pub enum Event {
-    StartDocument { version: u8 },
+    StartDocument { version: Version },
     EndDocument,
     StartHead,
     EndHead,
@@ -10,29 +15,45 @@ pub enum Event {
     EndBody,
     Title(String),
     Status(Option<u32>),
+    Fault(String),
+    FaultCode(String),
     StartOutline(Box<OutlineEvent>),
     EndOutline,
 }
 
+/// Contents of an `<outline>` element, without its children.
 #[derive(Debug, PartialEq)]
+#[non_exhaustive]
 pub enum OutlineEvent {
-    Group { text: String, key: String },
+    Group {
+        text: String,
+        key: String,
+        attributes: Attributes,
+    },
     Link(Link),
     Audio(Audio),
-    Text(String),
+    Text(Text),
+    /// Object outline, read together with its detail element.
+    Object(Object),
 }
 
 impl From<OutlineEvent> for Outline {
     fn from(outline: OutlineEvent) -> Outline {
         match outline {
-            OutlineEvent::Group { text, key } => Outline::Group(Group {
+            OutlineEvent::Group {
                 text,
                 key,
+                attributes,
+            } => Outline::Group(Group {
+                text,
+                key,
+                attributes,
                 outlines: vec![],
             }),
             OutlineEvent::Link(link) => Outline::Link(link),
             OutlineEvent::Audio(audio) => Outline::Audio(audio),
             OutlineEvent::Text(text) => Outline::Text(text),
+            OutlineEvent::Object(object) => Outline::Object(object),
         }
     }
 }
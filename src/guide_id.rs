@@ -0,0 +1,129 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// TuneIn identifier such as `s76368` or `g19`.
+///
+/// The letter prefix encodes the kind of the identified object and is followed
+/// by its number.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GuideId {
+    id: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GuideIdKind {
+    Station,
+    Program,
+    Topic,
+    Region,
+    Genre,
+    Category,
+    Language,
+    Artist,
+    Show,
+    Other(char),
+}
+
+/// Error returned when a string is not a valid [`GuideId`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseGuideIdError;
+
+impl GuideId {
+    /// Creates the id of `number` with the prefix of `kind`.
+    ///
+    /// Fails for [`GuideIdKind::Other`] prefixes which aren't ASCII lowercase
+    /// letters or which belong to a known kind, as such ids wouldn't parse back.
+    pub fn new(kind: GuideIdKind, number: u64) -> Result<GuideId, ParseGuideIdError> {
+        let prefix = kind.prefix();
+        if prefix.is_ascii_lowercase() && GuideIdKind::from_prefix(prefix) == kind {
+            Ok(GuideId {
+                id: format!("{}{}", prefix, number),
+            })
+        } else {
+            Err(ParseGuideIdError)
+        }
+    }
+
+    pub fn kind(&self) -> GuideIdKind {
+        GuideIdKind::from_prefix(self.prefix())
+    }
+
+    pub fn number(&self) -> u64 {
+        let start = self.prefix().len_utf8();
+        self.id[start..].parse().unwrap()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+
+    fn prefix(&self) -> char {
+        self.id.chars().next().unwrap()
+    }
+}
+
+impl GuideIdKind {
+    pub fn from_prefix(prefix: char) -> GuideIdKind {
+        match prefix {
+            's' => GuideIdKind::Station,
+            'p' => GuideIdKind::Program,
+            't' => GuideIdKind::Topic,
+            'r' => GuideIdKind::Region,
+            'g' => GuideIdKind::Genre,
+            'c' => GuideIdKind::Category,
+            'l' => GuideIdKind::Language,
+            'a' => GuideIdKind::Artist,
+            'm' => GuideIdKind::Show,
+            prefix => GuideIdKind::Other(prefix),
+        }
+    }
+
+    pub fn prefix(self) -> char {
+        match self {
+            GuideIdKind::Station => 's',
+            GuideIdKind::Program => 'p',
+            GuideIdKind::Topic => 't',
+            GuideIdKind::Region => 'r',
+            GuideIdKind::Genre => 'g',
+            GuideIdKind::Category => 'c',
+            GuideIdKind::Language => 'l',
+            GuideIdKind::Artist => 'a',
+            GuideIdKind::Show => 'm',
+            GuideIdKind::Other(prefix) => prefix,
+        }
+    }
+}
+
+impl FromStr for GuideId {
+    type Err = ParseGuideIdError;
+
+    fn from_str(value: &str) -> Result<GuideId, ParseGuideIdError> {
+        let mut chars = value.chars();
+        let valid_prefix = matches!(chars.next(), Some(c) if c.is_ascii_lowercase());
+        let number = chars.as_str();
+        let valid_number = !number.is_empty()
+            && number.bytes().all(|b| b.is_ascii_digit())
+            && number.parse::<u64>().is_ok();
+        if valid_prefix && valid_number {
+            Ok(GuideId {
+                id: value.to_string(),
+            })
+        } else {
+            Err(ParseGuideIdError)
+        }
+    }
+}
+
+impl fmt::Display for GuideId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+impl fmt::Display for ParseGuideIdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid guide id")
+    }
+}
+
+impl std::error::Error for ParseGuideIdError {}
@@ -0,0 +1,66 @@
+use crate::async_client::{AsyncTransport, BoxFuture};
+use crate::client::Response;
+use std::io;
+use tokio::io::AsyncRead;
+use tokio::stream::StreamExt;
+use url::Url;
+
+/// [`AsyncTransport`] backed by a plain HTTP hyper client.
+#[derive(Clone, Default)]
+pub struct HyperTransport {
+    client: hyper::Client<hyper::client::HttpConnector>,
+}
+
+impl HyperTransport {
+    pub fn new() -> HyperTransport {
+        HyperTransport::default()
+    }
+}
+
+impl AsyncTransport for HyperTransport {
+    type Body = Box<dyn AsyncRead + Unpin + Send>;
+
+    fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        self.get_with_headers(url, &[])
+    }
+
+    fn get_with_headers<'a>(
+        &'a self,
+        url: &'a Url,
+        headers: &'a [(String, String)],
+    ) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        Box::pin(async move {
+            let mut request = hyper::Request::get(url.as_str());
+            for (name, value) in headers {
+                request = request.header(name.as_str(), value.as_str());
+            }
+            let request = request
+                .body(hyper::Body::empty())
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+            let response = self.client.request(request).await.map_err(into_io_error)?;
+
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    let value = value.to_str().ok()?;
+                    Some((name.to_string(), value.to_string()))
+                })
+                .collect();
+            let body = response
+                .into_body()
+                .map(|chunk| chunk.map_err(into_io_error));
+
+            Ok(Response {
+                status,
+                headers,
+                body: Box::new(tokio::io::stream_reader(body)) as Self::Body,
+            })
+        })
+    }
+}
+
+fn into_io_error(error: hyper::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
@@ -0,0 +1,171 @@
+use crate::common::{Detail, Document, Head, Outline, Version};
+use crate::error::Error;
+use crate::event::OutlineEvent;
+use crate::read::Parsed;
+use crate::reader::{parse_detail, parse_outline, unexpected_element, ReaderConfig, Recovery};
+use serde::de::Error as _;
+use serde_json::{Map, Value};
+use std::io::Read;
+
+/// Reads a whole TuneIn JSON document, as returned with `render=json`.
+///
+/// Outline objects use the same keys as the OPML attributes, so the result
+/// is the same as [`read`](crate::read) gives for the equivalent OPML. JSON
+/// responses don't carry the OPML version, which is always set to 1.
+pub fn read_json<R: Read>(source: R) -> Result<Document, Error> {
+    read_json_with_config(source, ReaderConfig::default()).map(|parsed| parsed.document)
+}
+
+/// Reads a whole TuneIn JSON document using `config`.
+///
+/// In [lenient](crate::Mode::Lenient) mode, unknown elements and attribute
+/// values which fail to parse are reported in [`Parsed::warnings`].
+pub fn read_json_with_config<R: Read>(source: R, config: ReaderConfig) -> Result<Parsed, Error> {
+    let value: Value = serde_json::from_reader(source)?;
+    let mut warnings = vec![];
+    let mut recovery = Recovery {
+        mode: config.mode,
+        position: None,
+        warnings: &mut warnings,
+    };
+
+    let mut document = Document {
+        version: Version { major: 1, minor: 0 },
+        ..Default::default()
+    };
+    for (name, value) in as_object(&value, "document")? {
+        match name.as_str() {
+            "head" => read_head(&mut document.head, value, &mut recovery)?,
+            "body" => document.outlines = read_outlines(value, &mut recovery)?,
+            name => recovery.recover(unexpected_element(name))?,
+        }
+    }
+
+    Ok(Parsed { document, warnings })
+}
+
+fn read_head(head: &mut Head, value: &Value, recovery: &mut Recovery) -> Result<(), Error> {
+    for (name, value) in as_object(value, "head")? {
+        match name.as_str() {
+            "title" => head.title = scalar(value, name)?,
+            "status" => head.status = scalar(value, name)?.parse().ok(),
+            "fault" => head.fault = Some(scalar(value, name)?),
+            "fault_code" => head.fault_code = Some(scalar(value, name)?),
+            name => recovery.recover(unexpected_element(name))?,
+        }
+    }
+    Ok(())
+}
+
+fn read_outlines(value: &Value, recovery: &mut Recovery) -> Result<Vec<Outline>, Error> {
+    let mut outlines = vec![];
+    for value in as_array(value, "outlines")? {
+        if let Some(outline) = read_outline(value, recovery)? {
+            outlines.push(outline);
+        }
+    }
+    Ok(outlines)
+}
+
+fn read_outline(value: &Value, recovery: &mut Recovery) -> Result<Option<Outline>, Error> {
+    let fields = as_object(value, "outline")?;
+    let element = element_name(fields)?.unwrap_or("outline");
+    if element != "outline" {
+        recovery.recover(unexpected_element(element))?;
+        return Ok(None);
+    }
+
+    let attributes = scalar_fields(fields)?;
+    let attributes: Vec<_> = attributes
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    let children = fields.get("children");
+    let outline = match parse_outline(&attributes, recovery)? {
+        Some(OutlineEvent::Object(mut object)) => {
+            if let Some(children) = children {
+                object.detail = read_detail(children, recovery)?;
+            }
+            Outline::Object(object)
+        }
+        Some(outline) => {
+            let mut outline = Outline::from(outline);
+            match (&mut outline, children) {
+                (Outline::Group(group), Some(children)) => {
+                    group.outlines = read_outlines(children, recovery)?;
+                }
+                // Like the OPML reader, which skips each nested outline.
+                (_, Some(children)) => {
+                    for child in as_array(children, "children")? {
+                        let name = element_name(as_object(child, "outline")?)?;
+                        recovery.recover(unexpected_element(name.unwrap_or("outline")))?;
+                    }
+                }
+                (_, None) => {}
+            }
+            outline
+        }
+        None => return Ok(None),
+    };
+    Ok(Some(outline))
+}
+
+fn read_detail(value: &Value, recovery: &mut Recovery) -> Result<Option<Detail>, Error> {
+    let mut detail = None;
+    for value in as_array(value, "children")? {
+        let fields = as_object(value, "detail")?;
+        let name = element_name(fields)?.ok_or_else(|| invalid("detail without element"))?;
+        if detail.is_some() {
+            recovery.recover(unexpected_element(name))?;
+            continue;
+        }
+        detail = Some(parse_detail(name, scalar_fields(fields)?, recovery)?);
+    }
+    Ok(detail)
+}
+
+fn element_name(fields: &Map<String, Value>) -> Result<Option<&str>, Error> {
+    match fields.get("element") {
+        Some(value) => value
+            .as_str()
+            .map(Some)
+            .ok_or_else(|| invalid("element is not a string")),
+        None => Ok(None),
+    }
+}
+
+/// Fields of an outline or detail object other than `element` and `children`.
+fn scalar_fields(fields: &Map<String, Value>) -> Result<Vec<(String, String)>, Error> {
+    fields
+        .iter()
+        .filter(|(name, _)| *name != "element" && *name != "children")
+        .map(|(name, value)| Ok((name.clone(), scalar(value, name)?)))
+        .collect()
+}
+
+/// TuneIn sends all values as strings, but numbers and booleans are accepted too.
+fn scalar(value: &Value, name: &str) -> Result<String, Error> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Number(value) => Ok(value.to_string()),
+        Value::Bool(value) => Ok(value.to_string()),
+        Value::Null => Ok(String::new()),
+        _ => Err(invalid(&format!("{} is not a string", name))),
+    }
+}
+
+fn as_object<'a>(value: &'a Value, name: &str) -> Result<&'a Map<String, Value>, Error> {
+    value
+        .as_object()
+        .ok_or_else(|| invalid(&format!("{} is not an object", name)))
+}
+
+fn as_array<'a>(value: &'a Value, name: &str) -> Result<&'a Vec<Value>, Error> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid(&format!("{} is not an array", name)))
+}
+
+fn invalid(message: &str) -> Error {
+    serde_json::Error::custom(message).into()
+}
@@ -1,10 +1,44 @@
+#[cfg(feature = "async")]
+pub mod async_client;
+pub mod cache;
+pub mod client;
 mod common;
+pub mod crawl;
 mod error;
 mod event;
+mod guide_id;
+#[cfg(feature = "hyper-client")]
+mod hyper_transport;
+#[cfg(feature = "json")]
+mod json;
+pub mod paging;
+pub mod playlist;
+pub mod policy;
 mod read;
 mod reader;
 pub mod request;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod traverse;
+pub mod tune;
+pub mod visit;
+mod write;
+mod writer;
 
-pub use common::{Audio, Document, Format, Group, Head, Link, Outline, Version};
-pub use error::Error;
-pub use read::read;
+pub use common::{
+    Attributes, Audio, Detail, Document, Format, Group, Head, Item, Link, Object, Outline,
+    ProgramDetail, StationDetail, Text, Version,
+};
+pub use error::{Error, ErrorKind, Fault, FaultKind, Position};
+pub use event::{Event, OutlineEvent};
+pub use guide_id::{GuideId, GuideIdKind, ParseGuideIdError};
+#[cfg(feature = "json")]
+pub use json::{read_json, read_json_with_config};
+#[cfg(feature = "async")]
+pub use read::read_async;
+pub use read::{read, read_with_config, Parsed};
+pub use reader::{Events, Mode, Reader, ReaderConfig};
+pub use write::write;
+pub use writer::Writer;
+
+pub use url::Url;
@@ -0,0 +1,233 @@
+//! Following the continuation links of long listings.
+//!
+//! Browse responses are cut into pages. The last outline of a listing is then
+//! a link keyed `nextStations`, `nextShows` and the like, whose URL fetches the
+//! following page. [`Pages`] follows these links and yields each page with the
+//! continuation links removed.
+//!
+//! At most [`DEFAULT_PAGE_LIMIT`] pages are fetched unless another limit is
+//! set. [`Pages::truncated`] tells whether the listing went on past it.
+
+#[cfg(feature = "async")]
+use crate::async_client::{AsyncClient, AsyncTransport};
+use crate::client::{Client, Transport};
+use crate::common::{Document, Outline};
+use crate::error::{Error, ErrorKind};
+use std::collections::{HashSet, VecDeque};
+use url::Url;
+
+/// Number of pages fetched when no other limit is set.
+pub const DEFAULT_PAGE_LIMIT: usize = 50;
+
+/// Iterator over the pages of a listing, created by [`Client::pages`].
+///
+/// Each URL is fetched at most once, so continuation links pointing back to
+/// an earlier page don't cause a loop. Pages reporting a fault are returned
+/// as errors, and iteration stops after the first error.
+pub struct Pages<'a, T> {
+    client: &'a Client<T>,
+    state: State,
+}
+
+/// Asynchronous counterpart of [`Pages`], created by [`AsyncClient::pages`].
+#[cfg(feature = "async")]
+pub struct AsyncPages<'a, T> {
+    client: &'a AsyncClient<T>,
+    state: State,
+}
+
+struct State {
+    pending: VecDeque<Url>,
+    seen: HashSet<String>,
+    remaining: usize,
+}
+
+impl Document {
+    /// Removes the continuation links, at any depth, and returns their URLs.
+    pub fn take_continuations(&mut self) -> Vec<String> {
+        let mut urls = vec![];
+        self.retain(|outline| match outline {
+            Outline::Link(link) if link.key.starts_with("next") => {
+                urls.push(link.url.clone());
+                false
+            }
+            _ => true,
+        });
+        urls
+    }
+
+    /// Appends the outlines of a following page.
+    ///
+    /// Top-level groups with the same key as a group of this document are
+    /// merged into it, so a listing continued over several pages ends up in
+    /// a single group.
+    pub fn append_page(&mut self, page: Document) {
+        for outline in page.outlines {
+            let existing = match &outline {
+                Outline::Group(group) if !group.key.is_empty() => self
+                    .outlines
+                    .iter_mut()
+                    .find_map(|existing| match existing {
+                        Outline::Group(existing) if existing.key == group.key => Some(existing),
+                        _ => None,
+                    }),
+                _ => None,
+            };
+            match (existing, outline) {
+                (Some(existing), Outline::Group(group)) => existing.outlines.extend(group.outlines),
+                (_, outline) => self.outlines.push(outline),
+            }
+        }
+    }
+}
+
+impl State {
+    fn new(url: Url) -> State {
+        State {
+            pending: vec![url].into(),
+            seen: HashSet::new(),
+            remaining: DEFAULT_PAGE_LIMIT,
+        }
+    }
+
+    fn next_url(&mut self) -> Option<Url> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let url = self.pending.pop_front()?;
+        self.seen.insert(url.to_string());
+        self.remaining -= 1;
+        Some(url)
+    }
+
+    fn truncated(&self) -> bool {
+        self.remaining == 0 && !self.pending.is_empty()
+    }
+
+    /// Queues the continuations of a fetched page and strips them from it.
+    fn follow(&mut self, page_url: &Url, mut page: Document) -> Result<Document, Error> {
+        for continuation in page.take_continuations() {
+            let url = page_url.join(&continuation).map_err(|_| {
+                Error::new(ErrorKind::InvalidUrl {
+                    value: continuation,
+                })
+            })?;
+            if !self.seen.contains(url.as_str()) && !self.pending.contains(&url) {
+                self.pending.push_back(url);
+            }
+        }
+        Ok(page)
+    }
+
+    fn stop(&mut self) {
+        self.pending.clear();
+    }
+}
+
+impl<'a, T: Transport> Pages<'a, T> {
+    pub(crate) fn new(client: &'a Client<T>, url: Url) -> Pages<'a, T> {
+        Pages {
+            client,
+            state: State::new(url),
+        }
+    }
+
+    /// Fetches at most `max_pages` pages, [`DEFAULT_PAGE_LIMIT`] by default.
+    pub fn limit(mut self, max_pages: usize) -> Pages<'a, T> {
+        self.state.remaining = max_pages;
+        self
+    }
+
+    /// Whether the page limit was reached while continuation links were
+    /// left to follow, so that the listing is incomplete.
+    pub fn truncated(&self) -> bool {
+        self.state.truncated()
+    }
+
+    /// Fetches all pages and merges them with [`Document::append_page`].
+    ///
+    /// Check [`truncated`](Pages::truncated) afterwards to know whether the
+    /// page limit cut the listing short.
+    pub fn merge(&mut self) -> Result<Document, Error> {
+        let mut document = match self.next() {
+            Some(page) => page?,
+            None => return Ok(Document::default()),
+        };
+        for page in self {
+            document.append_page(page?);
+        }
+        Ok(document)
+    }
+}
+
+impl<T: Transport> Iterator for Pages<'_, T> {
+    type Item = Result<Document, Error>;
+
+    fn next(&mut self) -> Option<Result<Document, Error>> {
+        let url = self.state.next_url()?;
+        let page = self
+            .client
+            .fetch(&url)
+            .and_then(Document::into_result)
+            .and_then(|page| self.state.follow(&url, page));
+        if page.is_err() {
+            self.state.stop();
+        }
+        Some(page)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: AsyncTransport> AsyncPages<'a, T> {
+    pub(crate) fn new(client: &'a AsyncClient<T>, url: Url) -> AsyncPages<'a, T> {
+        AsyncPages {
+            client,
+            state: State::new(url),
+        }
+    }
+
+    /// Fetches at most `max_pages` pages, [`DEFAULT_PAGE_LIMIT`] by default.
+    pub fn limit(mut self, max_pages: usize) -> AsyncPages<'a, T> {
+        self.state.remaining = max_pages;
+        self
+    }
+
+    /// Whether the page limit was reached while continuation links were
+    /// left to follow, so that the listing is incomplete.
+    pub fn truncated(&self) -> bool {
+        self.state.truncated()
+    }
+
+    /// Fetches the next page, or returns `None` when there are no more.
+    pub async fn next(&mut self) -> Option<Result<Document, Error>> {
+        let url = self.state.next_url()?;
+        let page = match self
+            .client
+            .fetch(&url)
+            .await
+            .and_then(Document::into_result)
+        {
+            Ok(page) => self.state.follow(&url, page),
+            Err(error) => Err(error),
+        };
+        if page.is_err() {
+            self.state.stop();
+        }
+        Some(page)
+    }
+
+    /// Fetches all pages and merges them with [`Document::append_page`].
+    ///
+    /// Check [`truncated`](AsyncPages::truncated) afterwards to know whether
+    /// the page limit cut the listing short.
+    pub async fn merge(&mut self) -> Result<Document, Error> {
+        let mut document = match self.next().await {
+            Some(page) => page?,
+            None => return Ok(Document::default()),
+        };
+        while let Some(page) = self.next().await {
+            document.append_page(page?);
+        }
+        Ok(document)
+    }
+}
@@ -0,0 +1,224 @@
+//! Export of audio outlines as playlists for media players which don't read
+//! OPML.
+//!
+//! ```
+//! use tunein::playlist::{Playlist, PlaylistKind};
+//!
+//! let document = tunein::read(std::fs::File::open("tests/documents/sample_3.opml")?)?;
+//! let mut m3u = Vec::new();
+//! Playlist::from_document(&document).write(PlaylistKind::M3U, &mut m3u)?;
+//! assert!(String::from_utf8_lossy(&m3u).starts_with("#EXTM3U\n"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::common::{Audio, Document};
+use crate::error::{Error, ErrorKind};
+use std::io::{self, Write};
+use url::Url;
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
+
+/// Namespace of XSPF playlists.
+const XSPF_NAMESPACE: &str = "http://xspf.org/ns/0/";
+/// `rel` of the XSPF `meta` element holding the bitrate in kbps.
+const XSPF_BITRATE: &str = "http://opml.radiotime.com/bitrate";
+
+/// Playlist formats written by [`Playlist`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaylistKind {
+    /// Extended M3U, with the image as a `tvg-logo` attribute.
+    M3U,
+    /// PLS, which only carries titles.
+    PLS,
+    XSPF,
+}
+
+/// Audio outline of a playlist, with the stream it was resolved to, if any.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry<'a> {
+    pub audio: &'a Audio,
+    pub stream: Option<Url>,
+}
+
+/// Playlist of audio outlines.
+///
+/// Entries point to the `Tune.ashx` URL of their outline unless a stream was
+/// resolved for them with [`resolve_with`](Playlist::resolve_with). Outlines
+/// without any URL are left out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Playlist<'a> {
+    title: String,
+    entries: Vec<Entry<'a>>,
+}
+
+impl<'a> Playlist<'a> {
+    /// Creates an untitled playlist of `audios`.
+    pub fn new<I: IntoIterator<Item = &'a Audio>>(audios: I) -> Playlist<'a> {
+        Playlist {
+            title: String::new(),
+            entries: audios
+                .into_iter()
+                .map(|audio| Entry {
+                    audio,
+                    stream: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Creates a playlist of the audio outlines of a document, at any depth,
+    /// titled like the document.
+    pub fn from_document(document: &'a Document) -> Playlist<'a> {
+        Playlist::new(document.audios()).title(&document.head.title)
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Sets the stream of each entry to the URL `resolve` returns for it,
+    /// e.g. the first stream from [`Client::resolve_audio`](crate::client::Client::resolve_audio).
+    /// Entries for which it returns `None` keep their current URL.
+    pub fn resolve_with<F>(mut self, mut resolve: F) -> Self
+    where
+        F: FnMut(&Audio) -> Option<Url>,
+    {
+        for entry in &mut self.entries {
+            if let Some(url) = resolve(entry.audio) {
+                entry.stream = Some(url);
+            }
+        }
+        self
+    }
+
+    pub fn entries(&self) -> &[Entry<'a>] {
+        &self.entries
+    }
+
+    /// Writes the playlist in the given format.
+    pub fn write<W: Write>(&self, kind: PlaylistKind, sink: W) -> Result<(), Error> {
+        match kind {
+            PlaylistKind::M3U => self.write_m3u(sink),
+            PlaylistKind::PLS => self.write_pls(sink),
+            PlaylistKind::XSPF => self.write_xspf(sink),
+        }
+    }
+
+    /// Writes the playlist as extended M3U.
+    pub fn write_m3u<W: Write>(&self, mut sink: W) -> Result<(), Error> {
+        let mut write = || -> io::Result<()> {
+            writeln!(sink, "#EXTM3U")?;
+            if !self.title.is_empty() {
+                writeln!(sink, "#PLAYLIST:{}", line(&self.title))?;
+            }
+            for (entry, location) in self.located() {
+                let audio = entry.audio;
+                write!(sink, "#EXTINF:-1")?;
+                if !audio.image.is_empty() {
+                    write!(sink, " tvg-logo=\"{}\"", attribute(&audio.image))?;
+                }
+                if let Some(bitrate) = audio.bitrate {
+                    write!(sink, " bitrate=\"{}\"", bitrate)?;
+                }
+                writeln!(sink, ",{}", line(&entry.title()))?;
+                writeln!(sink, "{}", location)?;
+            }
+            Ok(())
+        };
+        write().map_err(|error| Error::new(ErrorKind::Io(error)))
+    }
+
+    /// Writes the playlist as PLS.
+    pub fn write_pls<W: Write>(&self, mut sink: W) -> Result<(), Error> {
+        let mut write = || -> io::Result<()> {
+            writeln!(sink, "[playlist]")?;
+            let mut count = 0;
+            for (index, (entry, location)) in self.located().enumerate() {
+                let number = index + 1;
+                writeln!(sink, "File{}={}", number, location)?;
+                writeln!(sink, "Title{}={}", number, line(&entry.title()))?;
+                writeln!(sink, "Length{}=-1", number)?;
+                count = number;
+            }
+            writeln!(sink, "NumberOfEntries={}", count)?;
+            writeln!(sink, "Version=2")
+        };
+        write().map_err(|error| Error::new(ErrorKind::Io(error)))
+    }
+
+    /// Writes the playlist as XSPF, with the subtext as annotation.
+    pub fn write_xspf<W: Write>(&self, sink: W) -> Result<(), Error> {
+        let mut writer = EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(sink);
+        writer.write(
+            XmlEvent::start_element("playlist")
+                .default_ns(XSPF_NAMESPACE)
+                .attr("version", "1"),
+        )?;
+        text_element(&mut writer, "title", &self.title)?;
+        writer.write(XmlEvent::start_element("trackList"))?;
+        for (entry, location) in self.located() {
+            let audio = entry.audio;
+            writer.write(XmlEvent::start_element("track"))?;
+            text_element(&mut writer, "location", location)?;
+            text_element(&mut writer, "title", &audio.text)?;
+            text_element(&mut writer, "annotation", &audio.subtext)?;
+            text_element(&mut writer, "image", &audio.image)?;
+            if let Some(bitrate) = audio.bitrate {
+                writer.write(XmlEvent::start_element("meta").attr("rel", XSPF_BITRATE))?;
+                writer.write(XmlEvent::characters(&bitrate.to_string()))?;
+                writer.write(XmlEvent::end_element())?;
+            }
+            writer.write(XmlEvent::end_element())?;
+        }
+        writer.write(XmlEvent::end_element())?;
+        writer.write(XmlEvent::end_element())?;
+        Ok(())
+    }
+
+    /// Entries with the URL to write for them.
+    fn located(&self) -> impl Iterator<Item = (&Entry<'a>, &str)> {
+        self.entries.iter().filter_map(|entry| {
+            let location = match &entry.stream {
+                Some(url) => url.as_str(),
+                None => entry.audio.url.as_str(),
+            };
+            Some((entry, location)).filter(|(_, location)| !location.is_empty())
+        })
+    }
+}
+
+impl Entry<'_> {
+    /// Text of the outline, followed by its subtext if any.
+    fn title(&self) -> String {
+        if self.audio.subtext.is_empty() {
+            self.audio.text.clone()
+        } else {
+            format!("{} - {}", self.audio.text, self.audio.subtext)
+        }
+    }
+}
+
+fn text_element<W: Write>(
+    writer: &mut EventWriter<W>,
+    name: &str,
+    text: &str,
+) -> Result<(), Error> {
+    if !text.is_empty() {
+        writer.write(XmlEvent::start_element(name))?;
+        writer.write(XmlEvent::characters(text))?;
+        writer.write(XmlEvent::end_element())?;
+    }
+    Ok(())
+}
+
+/// Keeps a value on a single line of a line-based playlist.
+fn line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// M3U attribute values can't be escaped, so their quotes are replaced.
+fn attribute(value: &str) -> String {
+    line(value).replace('"', "'")
+}
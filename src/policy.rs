@@ -0,0 +1,268 @@
+//! Retries, backoff, rate limiting and timeouts of client requests.
+//!
+//! A [`Policy`] is attached to a client with
+//! [`Client::with_policy`](crate::client::Client::with_policy). Requests
+//! failing with a transient error are retried after an exponentially
+//! growing delay. Transient errors are transport errors, HTTP statuses 429
+//! and 5xx, and documents whose [`Head::status`](crate::Head::status) is
+//! one of these.
+//!
+//! ```
+//! use std::time::Duration;
+//! use tunein::policy::Policy;
+//!
+//! let policy = Policy::new()
+//!     .retries(3)
+//!     .backoff(Duration::from_millis(200), Duration::from_secs(5))
+//!     .rate_limit(10.0, 5)
+//!     .timeout(Duration::from_secs(30));
+//! assert_eq!(policy.backoff_delay(2), Duration::from_millis(800));
+//! ```
+
+use crate::common::Document;
+use crate::error::{Error, ErrorKind, FaultKind};
+use crate::tune::Stream;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Slowest rate accepted by [`Policy::rate_limit`], one request per hour.
+const MIN_RATE: f64 = 1.0 / 3600.0;
+
+/// Configuration of retries, backoff, rate limiting and timeouts.
+///
+/// The default policy sends each request once, without any limit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    rate_limit: Option<(f64, u32)>,
+    timeout: Option<Duration>,
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy {
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            rate_limit: None,
+            timeout: None,
+        }
+    }
+}
+
+impl Policy {
+    pub fn new() -> Policy {
+        Policy::default()
+    }
+
+    /// Retries a request failing with a transient error up to `max_retries` times.
+    pub fn retries(mut self, max_retries: u32) -> Policy {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Waits `initial` before the first retry, doubling the delay before each
+    /// following one up to `max`. By default 100 ms and 10 s.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Policy {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Enables or disables the jitter of backoff delays, enabled by default.
+    ///
+    /// With jitter, each delay is picked at random between half of and the
+    /// full [`backoff_delay`](Policy::backoff_delay), so that clients failing
+    /// together don't retry together.
+    pub fn jitter(mut self, jitter: bool) -> Policy {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sends at most `per_second` requests per second to each host, on
+    /// average, allowing bursts of `burst` requests.
+    ///
+    /// Rates below one request per hour, including zero, negative rates and
+    /// NaN, are raised to it. An infinite rate removes the limit.
+    pub fn rate_limit(mut self, per_second: f64, burst: u32) -> Policy {
+        self.rate_limit = if per_second == f64::INFINITY {
+            None
+        } else {
+            Some((per_second.max(MIN_RATE), burst.max(1)))
+        };
+        self
+    }
+
+    /// Gives up on a request, retries and waits included, after `timeout`.
+    ///
+    /// A blocking [`Transport`](crate::client::Transport) can't be
+    /// interrupted, so the blocking client checks the timeout between
+    /// attempts only. Transports should also set their own timeouts.
+    pub fn timeout(mut self, timeout: Duration) -> Policy {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Delay before the retry number `retry`, counted from 0, without jitter.
+    pub fn backoff_delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+}
+
+/// Result of an attempt which may be retried.
+pub(crate) trait Outcome {
+    fn is_transient(&self) -> bool;
+}
+
+impl Outcome for Result<Document, Error> {
+    fn is_transient(&self) -> bool {
+        match self {
+            Ok(document) => document.head.status.map_or(false, is_transient_status),
+            Err(error) => is_transient(error),
+        }
+    }
+}
+
+impl Outcome for Result<Vec<Stream>, Error> {
+    fn is_transient(&self) -> bool {
+        match self {
+            Ok(_) => false,
+            Err(error) => is_transient(error),
+        }
+    }
+}
+
+pub(crate) fn is_transient(error: &Error) -> bool {
+    match error.kind() {
+        ErrorKind::Io(_) => true,
+        ErrorKind::HttpStatus { status } => is_transient_status(u32::from(*status)),
+        ErrorKind::Fault(fault) => matches!(
+            fault.kind(),
+            FaultKind::RateLimited | FaultKind::ServerError
+        ),
+        _ => false,
+    }
+}
+
+fn is_transient_status(status: u32) -> bool {
+    status == 429 || status >= 500
+}
+
+/// Policy with the state it needs across requests: token buckets and the
+/// jitter generator.
+pub(crate) struct Governor {
+    policy: Policy,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    random: Mutex<u64>,
+}
+
+struct Bucket {
+    /// Negative when requests are waiting for tokens.
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Next step after an attempt.
+pub(crate) enum Step {
+    Retry(Duration),
+    Stop,
+}
+
+impl Governor {
+    pub(crate) fn new(policy: Policy) -> Governor {
+        let seed = RandomState::new().build_hasher().finish();
+        Governor {
+            policy,
+            buckets: Mutex::default(),
+            random: Mutex::new(seed | 1),
+        }
+    }
+
+    pub(crate) fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.policy.timeout.map(|timeout| Instant::now() + timeout)
+    }
+
+    /// Takes a token for a request to `url`, returning how long to wait
+    /// before sending it.
+    pub(crate) fn acquire(&self, url: &Url) -> Duration {
+        let (per_second, burst) = match self.policy.rate_limit {
+            Some(rate_limit) => rate_limit,
+            None => return Duration::from_secs(0),
+        };
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets
+            .entry(url.host_str().unwrap_or_default().to_string())
+            .or_insert(Bucket {
+                tokens: f64::from(burst),
+                updated: now,
+            });
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * per_second;
+        bucket.tokens = (bucket.tokens + refill).min(f64::from(burst)) - 1.0;
+        bucket.updated = now;
+        if bucket.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / per_second)
+        }
+    }
+
+    /// Decides whether to retry after attempt number `attempt`, counted from 0.
+    pub(crate) fn next<O: Outcome>(
+        &self,
+        outcome: &O,
+        attempt: u32,
+        deadline: Option<Instant>,
+    ) -> Step {
+        if attempt >= self.policy.max_retries || !outcome.is_transient() {
+            return Step::Stop;
+        }
+        let delay = self.jittered(self.policy.backoff_delay(attempt));
+        if exceeds(deadline, delay) {
+            Step::Stop
+        } else {
+            Step::Retry(delay)
+        }
+    }
+
+    fn jittered(&self, delay: Duration) -> Duration {
+        if !self.policy.jitter {
+            return delay;
+        }
+        let mut random = self.random.lock().unwrap();
+        // xorshift64
+        *random ^= *random << 13;
+        *random ^= *random >> 7;
+        *random ^= *random << 17;
+        let fraction = (*random >> 11) as f64 / (1u64 << 53) as f64;
+        delay.mul_f64(0.5 + fraction / 2.0)
+    }
+}
+
+/// Whether waiting `delay` from now would pass `deadline`.
+pub(crate) fn exceeds(deadline: Option<Instant>, delay: Duration) -> bool {
+    deadline.map_or(false, |deadline| Instant::now() + delay >= deadline)
+}
+
+pub(crate) fn timeout_error() -> Error {
+    Error::new(ErrorKind::Timeout)
+}
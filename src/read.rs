@@ -1,42 +1,65 @@
 use crate::common::{Document, Group, Outline};
 use crate::error::Error;
 use crate::event::Event;
-use crate::reader::Reader;
+use crate::reader::{unexpected_element, Reader, ReaderConfig};
 
 use std::io::Read;
 
+/// Reads a whole TuneIn OPML document into memory.
+///
+/// Use [`Reader`](crate::Reader) to process the document event by event instead.
 pub fn read<R: Read>(source: R) -> Result<Document, Error> {
+    read_document(&mut Reader::new(source))
+}
+
+/// Document read together with the problems recovered from while reading it.
+#[derive(Debug)]
+pub struct Parsed {
+    pub document: Document,
+    pub warnings: Vec<Error>,
+}
+
+/// Reads a whole TuneIn OPML document into memory using `config`.
+///
+/// In [lenient](crate::Mode::Lenient) mode, skipped elements and attributes
+/// are reported in [`Parsed::warnings`].
+pub fn read_with_config<R: Read>(source: R, config: ReaderConfig) -> Result<Parsed, Error> {
+    let mut reader = Reader::with_config(source, config);
+    let document = read_document(&mut reader)?;
+    Ok(Parsed {
+        document,
+        warnings: reader.take_warnings(),
+    })
+}
+
+fn read_document<R: Read>(reader: &mut Reader<R>) -> Result<Document, Error> {
     let mut document = Document::default();
 
     let mut outline_stack: Vec<Outline> = vec![];
 
-    for event in Reader::new(source) {
-        match event? {
-            Event::StartDocument { version } => {
-                document.version.major = version;
-            }
-            Event::EndDocument => {
-                if !outline_stack.is_empty() {
-                    unreachable!("Outline stack is not empty");
-                }
+    loop {
+        match reader.next()? {
+            Event::StartDocument { version } => document.version = version,
+            Event::EndDocument if !outline_stack.is_empty() => {
+                unreachable!("Outline stack is not empty");
             }
+            Event::EndDocument => break,
             Event::Title(title) => document.head.title = title,
             Event::Status(status) => document.head.status = status,
+            Event::Fault(fault) => document.head.fault = Some(fault),
+            Event::FaultCode(code) => document.head.fault_code = Some(code),
             Event::StartOutline(outline) => outline_stack.push((*outline).into()),
             Event::EndOutline => {
                 let outline = outline_stack
                     .pop()
                     .expect("End/start elements doesn't match");
 
-                let outlines = outline_stack
-                    .last_mut()
-                    .map(|o| match *o {
-                        Outline::Group(Group {
-                            ref mut outlines, ..
-                        }) => outlines,
-                        _ => unreachable!("Last outline is not group"),
-                    })
-                    .unwrap_or(&mut document.outlines);
+                // The reader skips children of outlines other than groups.
+                let outlines = match outline_stack.last_mut() {
+                    Some(Outline::Group(Group { outlines, .. })) => outlines,
+                    Some(_) => return Err(unexpected_element("outline")),
+                    None => &mut document.outlines,
+                };
 
                 outlines.push(outline);
             }
@@ -46,3 +69,98 @@ pub fn read<R: Read>(source: R) -> Result<Document, Error> {
 
     Ok(document)
 }
+
+/// Reads a whole TuneIn OPML document from an asynchronous source.
+///
+/// The document is parsed as its bytes arrive, on a thread of the tokio
+/// blocking pool, so the source is never buffered whole and the calling task
+/// isn't blocked. It must be called within a tokio runtime.
+#[cfg(feature = "async")]
+pub async fn read_async<R: tokio::io::AsyncRead + Unpin>(mut source: R) -> Result<Document, Error> {
+    use std::sync::{mpsc, Arc};
+    use tokio::sync::Semaphore;
+
+    let (sender, receiver) = mpsc::channel();
+    let credits = Arc::new(Semaphore::new(ASYNC_CHUNKS));
+    let reader = ChunkReader {
+        receiver,
+        credits: credits.clone(),
+        chunk: vec![],
+        offset: 0,
+    };
+    let mut parse = tokio::task::spawn_blocking(move || read(reader));
+
+    // The parser may finish before the source ends, e.g. on an error.
+    let result = tokio::select! {
+        result = &mut parse => result,
+        () = send_chunks(&mut source, sender, &credits) => parse.await,
+    };
+    result.map_err(|error| {
+        Error::new(crate::error::ErrorKind::Io(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            error,
+        )))
+    })?
+}
+
+/// Sends the chunks of `source` to the parser, as long as it has credits left.
+#[cfg(feature = "async")]
+async fn send_chunks<R: tokio::io::AsyncRead + Unpin>(
+    source: &mut R,
+    sender: std::sync::mpsc::Sender<std::io::Result<Vec<u8>>>,
+    credits: &tokio::sync::Semaphore,
+) {
+    use tokio::io::AsyncReadExt;
+
+    let mut buffer = vec![0; ASYNC_CHUNK_SIZE];
+    loop {
+        credits.acquire().await.forget();
+        let chunk = match source.read(&mut buffer).await {
+            Ok(0) => return,
+            Ok(length) => Ok(buffer[..length].to_vec()),
+            Err(error) => Err(error),
+        };
+        let failed = chunk.is_err();
+        if sender.send(chunk).is_err() || failed {
+            return;
+        }
+    }
+}
+
+/// Size of the chunks read from an asynchronous source.
+#[cfg(feature = "async")]
+const ASYNC_CHUNK_SIZE: usize = 8 * 1024;
+/// Chunks read ahead of the parser.
+#[cfg(feature = "async")]
+const ASYNC_CHUNKS: usize = 4;
+
+/// Blocking reader over the chunks sent by [`read_async`].
+#[cfg(feature = "async")]
+struct ChunkReader {
+    receiver: std::sync::mpsc::Receiver<std::io::Result<Vec<u8>>>,
+    /// Returned as chunks are consumed, to let the next ones be read.
+    credits: std::sync::Arc<tokio::sync::Semaphore>,
+    chunk: Vec<u8>,
+    offset: usize,
+}
+
+#[cfg(feature = "async")]
+impl Read for ChunkReader {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        if self.offset == self.chunk.len() {
+            match self.receiver.recv() {
+                Ok(chunk) => {
+                    self.credits.add_permits(1);
+                    self.chunk = chunk?;
+                    self.offset = 0;
+                }
+                // The source has ended.
+                Err(_) => return Ok(0),
+            }
+        }
+        let length = buffer.len().min(self.chunk.len() - self.offset);
+        buffer[..length].copy_from_slice(&self.chunk[self.offset..self.offset + length]);
+        self.offset += length;
+        Ok(length)
+    }
+}
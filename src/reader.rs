@@ -1,21 +1,121 @@
-use crate::common::{Audio, Format, Link};
-use crate::error::Error;
+use crate::common::{
+    Attributes, Audio, Detail, Format, Item, Link, Object, ProgramDetail, StationDetail, Text,
+    Version,
+};
+use crate::error::{Error, ErrorKind, Position};
 use crate::event::{Event, OutlineEvent};
+use crate::guide_id::GuideId;
 use std::io::Read;
 use std::iter::{IntoIterator, Iterator};
+use xml::common::Position as _;
 
+/// Pull-based reader turning a TuneIn OPML document into a sequence of [`Event`]s.
+///
+/// Unlike [`read`](crate::read), the reader doesn't build a [`Document`](crate::Document),
+/// so outlines can be processed as they arrive and reading can stop at any point.
+///
+/// ```
+/// use tunein::{Event, OutlineEvent, Reader};
+///
+/// let source = r#"<opml version="1"><body><outline type="text" text="No stations"/></body></opml>"#;
+/// for event in Reader::new(source.as_bytes()) {
+///     if let Event::StartOutline(outline) = event? {
+///         if let OutlineEvent::Text(text) = *outline {
+///             assert_eq!(text.text, "No stations");
+///         }
+///     }
+/// }
+/// # Ok::<(), tunein::Error>(())
+/// ```
 pub struct Reader<R: Read> {
     reader: xml::reader::EventReader<R>,
+    config: ReaderConfig,
+    warnings: Vec<Error>,
+    end_outline: bool,
+    /// Whether each open outline is a group, the only kind with children.
+    open_outlines: Vec<bool>,
+    /// Whether `Event::EndDocument` has been returned.
+    ended: bool,
+}
+
+/// How the reader reacts to content it doesn't understand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Fail on the first problem.
+    Strict,
+    /// Skip unknown elements along with their children and replace unparseable
+    /// attribute values with `None`, recording each problem as a warning.
+    Lenient,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Mode {
+    fn default() -> Mode {
+        Mode::Strict
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReaderConfig {
+    pub mode: Mode,
+}
+
+impl ReaderConfig {
+    pub fn lenient() -> ReaderConfig {
+        ReaderConfig {
+            mode: Mode::Lenient,
+        }
+    }
 }
 
 impl<R: Read> Reader<R> {
+    /// Creates a strict reader over `source`.
     pub fn new(source: R) -> Reader<R> {
+        Reader::with_config(source, ReaderConfig::default())
+    }
+
+    /// Creates a reader over `source` using `config`.
+    pub fn with_config(source: R, config: ReaderConfig) -> Reader<R> {
         Reader {
             reader: xml::reader::EventReader::new(source),
+            config,
+            warnings: vec![],
+            end_outline: false,
+            open_outlines: vec![],
+            ended: false,
         }
     }
 
+    /// Problems recovered from so far in lenient mode.
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    /// Takes the problems recovered from so far, leaving the list empty.
+    pub fn take_warnings(&mut self) -> Vec<Error> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Reads the next event.
+    ///
+    /// Once the document has ended, `Event::EndDocument` is returned again.
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Event, Error> {
+        if self.ended {
+            return Ok(Event::EndDocument);
+        }
+        let event = self.next_event().map_err(|error| match error.position() {
+            Some(_) => error,
+            None => error.at(self.reader.position().into()),
+        });
+        self.ended = matches!(event, Ok(Event::EndDocument));
+        event
+    }
+
+    fn next_event(&mut self) -> Result<Event, Error> {
+        if std::mem::take(&mut self.end_outline) {
+            return Ok(Event::EndOutline);
+        }
         let mut content = String::new();
         loop {
             match self.reader.next()? {
@@ -23,24 +123,49 @@ impl<R: Read> Reader<R> {
                     ref name,
                     ref attributes,
                     ..
-                } => match name.local_name.as_str() {
-                    "head" => {
-                        return Ok(Event::StartHead);
-                    }
-                    "body" => {
-                        return Ok(Event::StartBody);
-                    }
-                    "title" | "status" => {}
-                    "opml" => {
-                        return parse_opml(&attributes);
+                } => {
+                    let in_leaf = self.open_outlines.last() == Some(&false);
+                    let mut recovery = self.recovery();
+                    match name.local_name.as_str() {
+                        "head" => {
+                            return Ok(Event::StartHead);
+                        }
+                        "body" => {
+                            return Ok(Event::StartBody);
+                        }
+                        "title" | "status" | "fault" | "fault_code" => {}
+                        "opml" => {
+                            return parse_opml(attributes);
+                        }
+                        "outline" if in_leaf => {
+                            recovery.recover(unexpected_element("outline"))?;
+                            self.skip_element()?;
+                        }
+                        "outline" => {
+                            match parse_outline(&attribute_pairs(attributes), &mut recovery)? {
+                                Some(OutlineEvent::Object(mut object)) => {
+                                    // The detail element is read eagerly, so the closing
+                                    // tag of the outline has already been consumed.
+                                    object.detail = self.read_detail()?;
+                                    self.end_outline = true;
+                                    return Ok(Event::StartOutline(Box::new(
+                                        OutlineEvent::Object(object),
+                                    )));
+                                }
+                                Some(outline) => {
+                                    let is_group = matches!(outline, OutlineEvent::Group { .. });
+                                    self.open_outlines.push(is_group);
+                                    return Ok(Event::StartOutline(Box::new(outline)));
+                                }
+                                None => self.skip_element()?,
+                            }
+                        }
+                        name => {
+                            recovery.recover(unexpected_element(name))?;
+                            self.skip_element()?;
+                        }
                     }
-                    "outline" => {
-                        return parse_outline(&attributes);
-                    }
-                    _ => {
-                        return Err(Error::UnexpectedElement);
-                    }
-                },
+                }
                 xml::reader::XmlEvent::Characters(s) => {
                     content = s;
                 }
@@ -50,121 +175,396 @@ impl<R: Read> Reader<R> {
                         "body" => Ok(Event::EndBody),
                         "title" => Ok(Event::Title(content)),
                         "status" => Ok(Event::Status(content.parse().ok())),
+                        "fault" => Ok(Event::Fault(content)),
+                        "fault_code" => Ok(Event::FaultCode(content)),
                         "opml" => Ok(Event::EndDocument),
-                        "outline" => Ok(Event::EndOutline),
-                        _ => Err(Error::UnexpectedElement),
+                        "outline" => {
+                            self.open_outlines.pop();
+                            Ok(Event::EndOutline)
+                        }
+                        name => Err(unexpected_element(name)),
                     }
                 }
-                xml::reader::XmlEvent::EndDocument => {}
+                xml::reader::XmlEvent::EndDocument => {
+                    return Err(Error::new(ErrorKind::MissingOpmlElement));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the detail element of an object outline, up to the end of the outline.
+    fn read_detail(&mut self) -> Result<Option<Detail>, Error> {
+        let mut detail = None;
+        loop {
+            match self.reader.next()? {
+                xml::reader::XmlEvent::StartElement { name, .. } if detail.is_none() => {
+                    let position = Some(self.reader.position().into());
+                    let fields = self.read_fields()?;
+                    let mut recovery = Recovery {
+                        mode: self.config.mode,
+                        position,
+                        warnings: &mut self.warnings,
+                    };
+                    detail = Some(parse_detail(&name.local_name, fields, &mut recovery)?);
+                }
+                xml::reader::XmlEvent::StartElement { name, .. } => {
+                    self.recovery()
+                        .recover(unexpected_element(&name.local_name))?;
+                    self.skip_element()?;
+                }
+                xml::reader::XmlEvent::EndElement { .. } => return Ok(detail),
+                _ => {}
+            }
+        }
+    }
+
+    fn read_fields(&mut self) -> Result<Vec<(String, String)>, Error> {
+        let mut fields = vec![];
+        loop {
+            match self.reader.next()? {
+                xml::reader::XmlEvent::StartElement { name, .. } => {
+                    let value = self.read_field()?;
+                    fields.push((name.local_name, value));
+                }
+                xml::reader::XmlEvent::EndElement { .. } => return Ok(fields),
+                _ => {}
+            }
+        }
+    }
+
+    fn read_field(&mut self) -> Result<String, Error> {
+        let mut value = String::new();
+        loop {
+            match self.reader.next()? {
+                xml::reader::XmlEvent::Characters(text) | xml::reader::XmlEvent::CData(text) => {
+                    value.push_str(&text)
+                }
+                xml::reader::XmlEvent::StartElement { name, .. } => {
+                    self.recovery()
+                        .recover(unexpected_element(&name.local_name))?;
+                    self.skip_element()?;
+                }
+                xml::reader::XmlEvent::EndElement { .. } => return Ok(value),
                 _ => {}
             }
         }
     }
+
+    fn recovery(&mut self) -> Recovery<'_> {
+        Recovery {
+            mode: self.config.mode,
+            position: Some(self.reader.position().into()),
+            warnings: &mut self.warnings,
+        }
+    }
+
+    fn skip_element(&mut self) -> Result<(), Error> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.reader.next()? {
+                xml::reader::XmlEvent::StartElement { .. } => depth += 1,
+                xml::reader::XmlEvent::EndElement { .. } => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Decides whether a problem fails the parse or is recorded as a warning.
+pub(crate) struct Recovery<'a> {
+    pub(crate) mode: Mode,
+    pub(crate) position: Option<Position>,
+    pub(crate) warnings: &'a mut Vec<Error>,
+}
+
+impl Recovery<'_> {
+    pub(crate) fn recover(&mut self, error: Error) -> Result<(), Error> {
+        let error = match self.position {
+            Some(position) => error.at(position),
+            None => error,
+        };
+        match self.mode {
+            Mode::Strict => Err(error),
+            Mode::Lenient => {
+                self.warnings.push(error);
+                Ok(())
+            }
+        }
+    }
+}
+
+pub(crate) fn unexpected_element(name: &str) -> Error {
+    Error::new(ErrorKind::UnexpectedElement {
+        name: name.to_string(),
+    })
+}
+
+fn attribute_pairs(attributes: &[xml::attribute::OwnedAttribute]) -> Vec<(&str, &str)> {
+    attributes
+        .iter()
+        .map(|attr| (attr.name.local_name.as_str(), attr.value.as_str()))
+        .collect()
 }
 
 fn parse_opml(attributes: &[xml::attribute::OwnedAttribute]) -> Result<Event, Error> {
     attributes
         .iter()
-        .find(|ref attr| attr.name.local_name == "version")
-        .ok_or(Error::MissingVersionAttr)
+        .find(|attr| attr.name.local_name == "version")
+        .ok_or_else(|| Error::new(ErrorKind::MissingVersionAttr))
         .and_then(|v| {
-            v.value
-                .parse::<u8>()
-                .map_err(|_| Error::InvalidVersionFormat)
+            parse_version(&v.value).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidVersionFormat {
+                    value: v.value.clone(),
+                })
+            })
         })
         .map(|version| Event::StartDocument { version })
 }
 
-fn parse_outline(attributes: &[xml::attribute::OwnedAttribute]) -> Result<Event, Error> {
-    attributes
-        .iter()
-        .find(|ref attr| attr.name.local_name == "type")
-        .map_or_else(
-            || parse_group(attributes),
-            |outline_type| match outline_type.value.as_str() {
-                "link" => parse_link(attributes),
-                "audio" => parse_audio(attributes),
-                "text" => parse_text(attributes),
-                _ => Err(Error::InvalidOutlineType),
-            },
-        )
-        .map(Box::new)
-        .map(Event::StartOutline)
-}
-
-fn parse_group(attributes: &[xml::attribute::OwnedAttribute]) -> Result<OutlineEvent, Error> {
+fn parse_version(value: &str) -> Option<Version> {
+    let mut parts = value.splitn(2, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |minor| minor.parse().ok())?;
+    Some(Version { major, minor })
+}
+
+pub(crate) fn parse_outline(
+    attributes: &[(&str, &str)],
+    recovery: &mut Recovery,
+) -> Result<Option<OutlineEvent>, Error> {
+    let outline_type = attributes.iter().find(|(name, _)| *name == "type");
+    match outline_type.map(|(_, value)| *value) {
+        None => parse_group(attributes).map(Some),
+        Some("link") => parse_link(attributes, recovery).map(Some),
+        Some("audio") => parse_audio(attributes, recovery).map(Some),
+        Some("text") => parse_text(attributes).map(Some),
+        Some("object") => parse_object(attributes).map(Some),
+        Some(value) => {
+            recovery.recover(Error::new(ErrorKind::InvalidOutlineType {
+                value: value.to_string(),
+            }))?;
+            Ok(None)
+        }
+    }
+}
+
+fn parse_group(attributes: &[(&str, &str)]) -> Result<OutlineEvent, Error> {
     let mut text = String::new();
     let mut key = String::new();
-    for attr in attributes {
-        match attr.name.local_name.as_str() {
-            "text" => text = attr.value.clone(),
-            "key" => key = attr.value.clone(),
-            _ => {}
+    let mut extra = Attributes::default();
+    for &(name, value) in attributes {
+        match name {
+            "text" => text = value.to_string(),
+            "key" => key = value.to_string(),
+            _ => extra.insert(name, value),
         }
     }
-    Ok(OutlineEvent::Group { text, key })
+    Ok(OutlineEvent::Group {
+        text,
+        key,
+        attributes: extra,
+    })
 }
 
-fn parse_link(attributes: &[xml::attribute::OwnedAttribute]) -> Result<OutlineEvent, Error> {
+fn parse_link(attributes: &[(&str, &str)], recovery: &mut Recovery) -> Result<OutlineEvent, Error> {
     let mut link = Link::default();
-    for attr in attributes {
-        match attr.name.local_name.as_str() {
-            "text" => link.text = attr.value.clone(),
-            "URL" => link.url = attr.value.clone(),
-            "key" => link.key = attr.value.clone(),
-            "guide_id" => link.guide_id = attr.value.clone(),
-            _ => {}
+    for &(name, value) in attributes {
+        match name {
+            "text" => link.text = value.to_string(),
+            "URL" => link.url = value.to_string(),
+            "key" => link.key = value.to_string(),
+            "guide_id" => link.guide_id = parse_guide_id("guide_id", value, recovery)?,
+            "item" => link.item = parse_item(value),
+            "type" => {}
+            _ => link.attributes.insert(name, value),
         }
     }
     Ok(OutlineEvent::Link(link))
 }
 
-fn parse_audio(attributes: &[xml::attribute::OwnedAttribute]) -> Result<OutlineEvent, Error> {
+fn parse_audio(
+    attributes: &[(&str, &str)],
+    recovery: &mut Recovery,
+) -> Result<OutlineEvent, Error> {
     let mut audio = Audio::default();
-    for attr in attributes {
-        match attr.name.local_name.as_str() {
-            "text" => audio.text = attr.value.clone(),
-            "subtext" => audio.subtext = attr.value.clone(),
-            "URL" => audio.url = attr.value.clone(),
+    for &(name, value) in attributes {
+        match name {
+            "text" => audio.text = value.to_string(),
+            "subtext" => audio.subtext = value.to_string(),
+            "URL" => audio.url = value.to_string(),
             "bitrate" => {
-                audio.bitrate = attr
-                    .value
-                    .parse()
-                    .map_err(|_| Error::InvalidBitrateFormat)?
+                audio.bitrate = parse_number(value, recovery, |value| {
+                    ErrorKind::InvalidBitrateFormat { value }
+                })?
             }
             "reliability" => {
-                audio.reliability = attr
-                    .value
-                    .parse()
-                    .map_err(|_| Error::InvalidReliabilityFormat)?
+                audio.reliability = parse_number(value, recovery, |value| {
+                    ErrorKind::InvalidReliabilityFormat { value }
+                })?
             }
-            "formats" => {
-                audio.format = match attr.value.as_str() {
-                    "mp3" => Format::MP3,
-                    _ => Format::Unknown,
-                }
+            "formats" => audio.formats = Format::parse_list(value),
+            "item" => audio.item = parse_item(value),
+            "image" => audio.image = value.to_string(),
+            "guide_id" => audio.guide_id = parse_guide_id("guide_id", value, recovery)?,
+            "genre_id" => audio.genre_id = parse_guide_id("genre_id", value, recovery)?,
+            "now_playing_id" => {
+                audio.now_playing_id = parse_guide_id("now_playing_id", value, recovery)?
             }
-            "item" => audio.item = attr.value.clone(),
-            "image" => audio.image = attr.value.clone(),
-            "guide_id" => audio.guide_id = attr.value.clone(),
-            "genre_id" => audio.genre_id = attr.value.clone(),
-            "now_playing_id" => audio.now_playing_id = attr.value.clone(),
-            "preset_id" => audio.preset_id = attr.value.clone(),
-            _ => {}
+            "preset_id" => audio.preset_id = parse_guide_id("preset_id", value, recovery)?,
+            "type" => {}
+            _ => audio.attributes.insert(name, value),
         }
     }
     Ok(OutlineEvent::Audio(audio))
 }
 
-fn parse_text(attributes: &[xml::attribute::OwnedAttribute]) -> Result<OutlineEvent, Error> {
-    let text = attributes
-        .iter()
-        .find(|&attr| attr.name.local_name == "text")
-        .map(|attr| attr.value.clone())
-        .unwrap_or_default();
+fn parse_number<T: std::str::FromStr>(
+    value: &str,
+    recovery: &mut Recovery,
+    error: impl FnOnce(String) -> ErrorKind,
+) -> Result<Option<T>, Error> {
+    match value.parse() {
+        Ok(number) => Ok(Some(number)),
+        Err(_) => {
+            recovery.recover(Error::new(error(value.to_string())))?;
+            Ok(None)
+        }
+    }
+}
+
+fn parse_guide_id(
+    attribute: &str,
+    value: &str,
+    recovery: &mut Recovery,
+) -> Result<Option<GuideId>, Error> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    match value.parse() {
+        Ok(guide_id) => Ok(Some(guide_id)),
+        Err(_) => {
+            recovery.recover(Error::new(ErrorKind::InvalidGuideId {
+                attribute: attribute.to_string(),
+                value: value.to_string(),
+            }))?;
+            Ok(None)
+        }
+    }
+}
+
+fn parse_item(value: &str) -> Option<Item> {
+    if value.is_empty() {
+        None
+    } else {
+        value.parse().ok()
+    }
+}
+
+fn parse_text(attributes: &[(&str, &str)]) -> Result<OutlineEvent, Error> {
+    let mut text = Text::default();
+    for &(name, value) in attributes {
+        match name {
+            "text" => text.text = value.to_string(),
+            "type" => {}
+            _ => text.attributes.insert(name, value),
+        }
+    }
     Ok(OutlineEvent::Text(text))
 }
 
+fn parse_object(attributes: &[(&str, &str)]) -> Result<OutlineEvent, Error> {
+    let mut object = Object::default();
+    for &(name, value) in attributes {
+        match name {
+            "text" => object.text = value.to_string(),
+            "type" => {}
+            _ => object.attributes.insert(name, value),
+        }
+    }
+    Ok(OutlineEvent::Object(object))
+}
+
+pub(crate) fn parse_detail(
+    name: &str,
+    fields: Vec<(String, String)>,
+    recovery: &mut Recovery,
+) -> Result<Detail, Error> {
+    match name {
+        "station" => parse_station(fields, recovery).map(Detail::Station),
+        "program" => parse_program(fields, recovery).map(Detail::Program),
+        name => Ok(Detail::Other {
+            name: name.to_string(),
+            fields: fields.into_iter().collect(),
+        }),
+    }
+}
+
+fn parse_station(
+    fields: Vec<(String, String)>,
+    recovery: &mut Recovery,
+) -> Result<StationDetail, Error> {
+    let mut station = StationDetail::default();
+    for (name, value) in fields {
+        match name.as_str() {
+            "guide_id" => station.guide_id = parse_guide_id("guide_id", &value, recovery)?,
+            "preset_id" => station.preset_id = parse_guide_id("preset_id", &value, recovery)?,
+            "name" => station.name = value,
+            "call_sign" => station.call_sign = value,
+            "slogan" => station.slogan = value,
+            "description" => station.description = value,
+            "frequency" => station.frequency = value,
+            "band" => station.band = value,
+            "url" => station.url = value,
+            "logo" => station.logo = value,
+            "location" => station.location = value,
+            "genre_id" => station.genre_id = parse_guide_id("genre_id", &value, recovery)?,
+            "genre_name" => station.genre_name = value,
+            "region_id" => station.region_id = parse_guide_id("region_id", &value, recovery)?,
+            "language" => station.language = value,
+            "twitter_id" => station.twitter_id = value,
+            "is_available" => station.is_available = parse_bool(&value),
+            "is_music" => station.is_music = parse_bool(&value),
+            "has_song" => station.has_song = parse_bool(&value),
+            "has_schedule" => station.has_schedule = parse_bool(&value),
+            "has_topics" => station.has_topics = parse_bool(&value),
+            _ => station.fields.insert(name, value),
+        }
+    }
+    Ok(station)
+}
+
+fn parse_program(
+    fields: Vec<(String, String)>,
+    recovery: &mut Recovery,
+) -> Result<ProgramDetail, Error> {
+    let mut program = ProgramDetail::default();
+    for (name, value) in fields {
+        match name.as_str() {
+            "guide_id" => program.guide_id = parse_guide_id("guide_id", &value, recovery)?,
+            "name" => program.name = value,
+            "description" => program.description = value,
+            "hosts" => program.hosts = value,
+            "url" => program.url = value,
+            "logo" => program.logo = value,
+            "location" => program.location = value,
+            "genre_id" => program.genre_id = parse_guide_id("genre_id", &value, recovery)?,
+            "genre_name" => program.genre_name = value,
+            "language" => program.language = value,
+            "twitter_id" => program.twitter_id = value,
+            "is_available" => program.is_available = parse_bool(&value),
+            "has_topics" => program.has_topics = parse_bool(&value),
+            _ => program.fields.insert(name, value),
+        }
+    }
+    Ok(program)
+}
+
+fn parse_bool(value: &str) -> bool {
+    value.trim().eq_ignore_ascii_case("true")
+}
+
 impl<R: Read> IntoIterator for Reader<R> {
     type Item = Result<Event, Error>;
     type IntoIter = Events<R>;
@@ -177,11 +577,21 @@ impl<R: Read> IntoIterator for Reader<R> {
     }
 }
 
+/// Iterator over the events of a [`Reader`].
+///
+/// The iterator ends after `Event::EndDocument` or the first error.
 pub struct Events<R: Read> {
     reader: Reader<R>,
     finished: bool,
 }
 
+impl<R: Read> Events<R> {
+    /// Problems recovered from so far in lenient mode.
+    pub fn warnings(&self) -> &[Error] {
+        self.reader.warnings()
+    }
+}
+
 impl<R: Read> Iterator for Events<R> {
     type Item = Result<Event, Error>;
 
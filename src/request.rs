@@ -1,8 +1,223 @@
+use crate::common::{Format, Item};
+use crate::error::{Error, ErrorKind};
+use crate::guide_id::GuideId;
+use url::Url;
+
+pub const BASE_URI: &str = "http://opml.radiotime.com/";
 pub const BROWSE_URI: &str = "http://opml.radiotime.com/Browse.ashx";
 
+#[deprecated(note = "use `Request::search` which encodes the query itself")]
 pub fn search_uri<T: std::fmt::Display>(percent_encoded_query: &T) -> String {
     format!(
         "http://opml.radiotime.com/Search.ashx?query={}",
         percent_encoded_query
     )
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    Browse,
+    Search,
+    Tune,
+    Describe,
+    Options,
+    Preset,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PresetAction {
+    Add,
+    Remove,
+}
+
+/// Response format requested with the `render` parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Render {
+    Xml,
+    Json,
+}
+
+/// Request to one of the TuneIn OPML endpoints.
+///
+/// Parameters are kept in the order they were set and percent-encoded when
+/// the [`Url`] is built.
+///
+/// ```
+/// use tunein::request::Request;
+///
+/// let url = Request::search("Kraków").locale("pl").url();
+/// assert_eq!(
+///     url.as_str(),
+///     "http://opml.radiotime.com/Search.ashx?query=Krak%C3%B3w&locale=pl"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    base: Url,
+    endpoint: Endpoint,
+    params: Vec<(&'static str, String)>,
+}
+
+impl Endpoint {
+    pub fn path(self) -> &'static str {
+        match self {
+            Endpoint::Browse => "Browse.ashx",
+            Endpoint::Search => "Search.ashx",
+            Endpoint::Tune => "Tune.ashx",
+            Endpoint::Describe => "Describe.ashx",
+            Endpoint::Options => "Options.ashx",
+            Endpoint::Preset => "Preset.ashx",
+        }
+    }
+}
+
+impl Request {
+    pub fn new(endpoint: Endpoint) -> Request {
+        Request {
+            base: Url::parse(BASE_URI).unwrap(),
+            endpoint,
+            params: vec![],
+        }
+    }
+
+    /// Root of the directory.
+    pub fn browse() -> Request {
+        Request::new(Endpoint::Browse)
+    }
+
+    pub fn search(query: &str) -> Request {
+        Request::new(Endpoint::Search).param("query", query)
+    }
+
+    pub fn tune(id: &GuideId) -> Request {
+        Request::new(Endpoint::Tune).id(id)
+    }
+
+    pub fn describe(id: &GuideId) -> Request {
+        Request::new(Endpoint::Describe).id(id)
+    }
+
+    pub fn options(id: &GuideId) -> Request {
+        Request::new(Endpoint::Options).id(id)
+    }
+
+    pub fn preset(action: PresetAction, id: &GuideId) -> Request {
+        let action = match action {
+            PresetAction::Add => "add",
+            PresetAction::Remove => "remove",
+        };
+        Request::new(Endpoint::Preset).param("c", action).id(id)
+    }
+
+    /// Sends the request to another server, e.g. a proxy or a test fixture.
+    ///
+    /// Endpoint paths are resolved against `base`, so it should end with a slash.
+    /// Fails for URLs which can't be a base, such as `mailto:` URLs.
+    pub fn base(mut self, base: Url) -> Result<Request, Error> {
+        self.base = check_base(base)?;
+        Ok(self)
+    }
+
+    pub fn endpoint(&self) -> Endpoint {
+        self.endpoint
+    }
+
+    /// Browse category such as `local`, `music` or `lang` (`c=`).
+    pub fn category(self, category: &str) -> Request {
+        self.param("c", category)
+    }
+
+    pub fn id(self, id: &GuideId) -> Request {
+        self.param("id", id.as_str())
+    }
+
+    pub fn filter(self, filter: &str) -> Request {
+        self.param("filter", filter)
+    }
+
+    pub fn offset(self, offset: u32) -> Request {
+        self.param("offset", &offset.to_string())
+    }
+
+    /// Kinds of objects returned by a search.
+    pub fn types(self, types: &[Item]) -> Request {
+        let types = types
+            .iter()
+            .map(Item::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        self.param("types", &types)
+    }
+
+    pub fn partner_id(self, partner_id: &str) -> Request {
+        self.param("partnerId", partner_id)
+    }
+
+    pub fn serial(self, serial: &str) -> Request {
+        self.param("serial", serial)
+    }
+
+    /// Stream formats the player can decode.
+    pub fn formats(self, formats: &[Format]) -> Request {
+        self.param("formats", &Format::join_list(formats))
+    }
+
+    pub fn locale(self, locale: &str) -> Request {
+        self.param("locale", locale)
+    }
+
+    pub fn username(self, username: &str) -> Request {
+        self.param("username", username)
+    }
+
+    pub fn render(self, render: Render) -> Request {
+        let render = match render {
+            Render::Xml => "xml",
+            Render::Json => "json",
+        };
+        self.param("render", render)
+    }
+
+    pub fn latlon(self, latitude: f64, longitude: f64) -> Request {
+        self.param("latlon", &format!("{},{}", latitude, longitude))
+    }
+
+    fn param(mut self, name: &'static str, value: &str) -> Request {
+        match self.params.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.params.push((name, value.to_string())),
+        }
+        self
+    }
+
+    pub fn url(&self) -> Url {
+        self.url_at(&self.base)
+    }
+
+    /// URL of the request sent to `base`, which must have passed [`check_base`].
+    pub(crate) fn url_at(&self, base: &Url) -> Url {
+        // Relative paths always resolve against a checked base.
+        let mut url = base.join(self.endpoint.path()).unwrap();
+        if !self.params.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.params);
+        }
+        url
+    }
+}
+
+impl From<&Request> for Url {
+    fn from(request: &Request) -> Url {
+        request.url()
+    }
+}
+
+/// Returns `base` if relative URLs can be resolved against it.
+pub(crate) fn check_base(base: Url) -> Result<Url, Error> {
+    if base.cannot_be_a_base() {
+        Err(Error::new(ErrorKind::InvalidBaseUrl {
+            value: base.to_string(),
+        }))
+    } else {
+        Ok(base)
+    }
+}
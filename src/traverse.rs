@@ -0,0 +1,209 @@
+//! Iterators and queries over the outline tree of a [`Document`] or [`Group`].
+//!
+//! ```
+//! let document = tunein::read(std::fs::File::open("tests/documents/sample_2.opml")?)?;
+//! let good: Vec<_> = document
+//!     .audios()
+//!     .filter(|audio| audio.bitrate >= Some(32) && audio.reliability >= Some(85))
+//!     .map(|audio| audio.text.as_str())
+//!     .collect();
+//! assert_eq!(
+//!     good,
+//!     vec!["PR R Krakow Nowy Sacz 90.0 (Rock)", "PR R Krakow Tarnow 101.0 (Rock)"]
+//! );
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::common::{Audio, Document, Group, Link, Outline};
+use crate::guide_id::GuideId;
+
+/// Outline visited by [`Iter`], together with its position in the tree.
+#[derive(Clone, Debug)]
+pub struct Entry<'a> {
+    pub outline: &'a Outline,
+    /// Number of groups above the outline, 0 for top-level outlines.
+    pub depth: usize,
+    /// Groups leading to the outline, outermost first.
+    pub path: Vec<&'a Group>,
+}
+
+/// Depth-first iterator visiting each group before its children.
+pub struct Iter<'a> {
+    stack: Vec<std::slice::Iter<'a, Outline>>,
+    path: Vec<&'a Group>,
+}
+
+/// Iterator over the audio outlines of a tree, in depth-first order.
+pub struct Audios<'a> {
+    iter: Iter<'a>,
+}
+
+/// Iterator over the link outlines of a tree, in depth-first order.
+pub struct Links<'a> {
+    iter: Iter<'a>,
+}
+
+impl<'a> Iter<'a> {
+    fn new(outlines: &'a [Outline]) -> Iter<'a> {
+        Iter {
+            stack: vec![outlines.iter()],
+            path: vec![],
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Entry<'a>;
+
+    fn next(&mut self) -> Option<Entry<'a>> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(outline) => {
+                    let entry = Entry {
+                        outline,
+                        depth: self.path.len(),
+                        path: self.path.clone(),
+                    };
+                    if let Outline::Group(group) = outline {
+                        self.stack.push(group.outlines.iter());
+                        self.path.push(group);
+                    }
+                    return Some(entry);
+                }
+                None => {
+                    self.stack.pop();
+                    self.path.pop();
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Audios<'a> {
+    type Item = &'a Audio;
+
+    fn next(&mut self) -> Option<&'a Audio> {
+        self.iter.find_map(|entry| match entry.outline {
+            Outline::Audio(audio) => Some(audio),
+            _ => None,
+        })
+    }
+}
+
+impl<'a> Iterator for Links<'a> {
+    type Item = &'a Link;
+
+    fn next(&mut self) -> Option<&'a Link> {
+        self.iter.find_map(|entry| match entry.outline {
+            Outline::Link(link) => Some(link),
+            _ => None,
+        })
+    }
+}
+
+impl Document {
+    /// Visits all outlines depth-first.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter::new(&self.outlines)
+    }
+
+    pub fn audios(&self) -> Audios<'_> {
+        Audios { iter: self.iter() }
+    }
+
+    pub fn links(&self) -> Links<'_> {
+        Links { iter: self.iter() }
+    }
+
+    /// First outline, in depth-first order, identified by `id`.
+    pub fn find_by_guide_id(&self, id: &GuideId) -> Option<&Outline> {
+        find_by_guide_id(self.iter(), id)
+    }
+
+    /// First group or link, in depth-first order, with the given key.
+    pub fn find_by_key(&self, key: &str) -> Option<&Outline> {
+        find_by_key(self.iter(), key)
+    }
+
+    /// Copy of the document with only the outlines matching `predicate`.
+    ///
+    /// A matching group is kept whole. Other groups are kept with their
+    /// matching descendants, or dropped if there are none.
+    pub fn filter<P: Fn(&Outline) -> bool>(&self, predicate: P) -> Document {
+        Document {
+            version: self.version.clone(),
+            head: self.head.clone(),
+            outlines: filter(&self.outlines, &predicate),
+        }
+    }
+}
+
+impl Group {
+    /// Visits all descendants depth-first.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter::new(&self.outlines)
+    }
+
+    pub fn audios(&self) -> Audios<'_> {
+        Audios { iter: self.iter() }
+    }
+
+    pub fn links(&self) -> Links<'_> {
+        Links { iter: self.iter() }
+    }
+
+    /// First descendant, in depth-first order, identified by `id`.
+    pub fn find_by_guide_id(&self, id: &GuideId) -> Option<&Outline> {
+        find_by_guide_id(self.iter(), id)
+    }
+
+    /// First descendant group or link, in depth-first order, with the given key.
+    pub fn find_by_key(&self, key: &str) -> Option<&Outline> {
+        find_by_key(self.iter(), key)
+    }
+
+    /// Copy of the group with only the descendants matching `predicate`.
+    ///
+    /// Nested groups are handled as in [`Document::filter`].
+    pub fn filter<P: Fn(&Outline) -> bool>(&self, predicate: P) -> Group {
+        Group {
+            text: self.text.clone(),
+            key: self.key.clone(),
+            attributes: self.attributes.clone(),
+            outlines: filter(&self.outlines, &predicate),
+        }
+    }
+}
+
+fn find_by_guide_id<'a>(mut iter: Iter<'a>, id: &GuideId) -> Option<&'a Outline> {
+    iter.find(|entry| entry.outline.guide_id() == Some(id))
+        .map(|entry| entry.outline)
+}
+
+fn find_by_key<'a>(mut iter: Iter<'a>, key: &str) -> Option<&'a Outline> {
+    iter.find(|entry| !key.is_empty() && entry.outline.key() == key)
+        .map(|entry| entry.outline)
+}
+
+fn filter<P: Fn(&Outline) -> bool>(outlines: &[Outline], predicate: &P) -> Vec<Outline> {
+    outlines
+        .iter()
+        .filter_map(|outline| match outline {
+            _ if predicate(outline) => Some(outline.clone()),
+            Outline::Group(group) => {
+                let group = Group {
+                    text: group.text.clone(),
+                    key: group.key.clone(),
+                    attributes: group.attributes.clone(),
+                    outlines: filter(&group.outlines, predicate),
+                };
+                if group.outlines.is_empty() {
+                    None
+                } else {
+                    Some(Outline::Group(group))
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
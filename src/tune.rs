@@ -0,0 +1,253 @@
+use crate::common::{Audio, Format};
+use crate::error::{Error, ErrorKind};
+use url::Url;
+
+/// Playlist format returned by `Tune.ashx`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    M3U,
+    PLS,
+    ASX,
+    /// One URL per line.
+    Plain,
+}
+
+/// Candidate stream found in a playlist.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stream {
+    pub url: Url,
+    pub title: Option<String>,
+    pub format: Option<Format>,
+    pub bitrate: Option<u16>,
+}
+
+impl PlaylistFormat {
+    pub fn detect(content: &str) -> PlaylistFormat {
+        let start = content.trim_start().to_ascii_lowercase();
+        if start.starts_with("[playlist]") {
+            PlaylistFormat::PLS
+        } else if start.contains("<asx") {
+            PlaylistFormat::ASX
+        } else if start.starts_with("#extm3u") {
+            PlaylistFormat::M3U
+        } else {
+            PlaylistFormat::Plain
+        }
+    }
+}
+
+impl Stream {
+    fn new(url: Url, title: Option<String>) -> Stream {
+        let format = format_hint(&url);
+        let bitrate = title.as_deref().and_then(bitrate_hint);
+        Stream {
+            url,
+            title,
+            format,
+            bitrate,
+        }
+    }
+
+    /// Fills hints missing in the playlist with the ones known from the outline.
+    pub fn with_audio_hints(mut self, audio: &Audio) -> Stream {
+        if self.bitrate.is_none() {
+            self.bitrate = audio.bitrate;
+        }
+        if self.format.is_none() && audio.formats.len() == 1 {
+            self.format = audio.formats.first().cloned();
+        }
+        self
+    }
+}
+
+/// Parses a `Tune.ashx` response into candidate streams, in playlist order.
+///
+/// Entries which aren't absolute URLs are skipped.
+pub fn parse_playlist(content: &str) -> Vec<Stream> {
+    match PlaylistFormat::detect(content) {
+        PlaylistFormat::M3U | PlaylistFormat::Plain => parse_m3u(content),
+        PlaylistFormat::PLS => parse_pls(content),
+        PlaylistFormat::ASX => parse_asx(content),
+    }
+}
+
+pub(crate) fn parse_response(content: &[u8]) -> Result<Vec<Stream>, Error> {
+    let streams = parse_playlist(&String::from_utf8_lossy(content));
+    if streams.is_empty() {
+        Err(Error::new(ErrorKind::EmptyPlaylist))
+    } else {
+        Ok(streams)
+    }
+}
+
+pub(crate) fn audio_url(audio: &Audio) -> Result<Url, Error> {
+    Url::parse(&audio.url).map_err(|_| {
+        Error::new(ErrorKind::InvalidUrl {
+            value: audio.url.clone(),
+        })
+    })
+}
+
+fn parse_m3u(content: &str) -> Vec<Stream> {
+    let mut streams = vec![];
+    let mut title = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            title = info
+                .split_once(',')
+                .map(|(_, title)| title.trim())
+                .filter(|t| !t.is_empty())
+                .map(str::to_string);
+        } else if !line.is_empty() && !line.starts_with('#') {
+            if let Ok(url) = Url::parse(line) {
+                streams.push(Stream::new(url, title.take()));
+            }
+        }
+    }
+    streams
+}
+
+fn parse_pls(content: &str) -> Vec<Stream> {
+    let mut entries: Vec<(u32, Option<Url>, Option<String>)> = vec![];
+    for line in content.lines().map(str::trim) {
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim().to_ascii_lowercase(), value.trim()),
+            None => continue,
+        };
+        let (field, index) = if let Some(index) = key.strip_prefix("file") {
+            ("file", index)
+        } else if let Some(index) = key.strip_prefix("title") {
+            ("title", index)
+        } else {
+            continue;
+        };
+        let index: u32 = match index.parse() {
+            Ok(index) => index,
+            Err(_) => continue,
+        };
+        let position = match entries.iter().position(|(i, _, _)| *i == index) {
+            Some(position) => position,
+            None => {
+                entries.push((index, None, None));
+                entries.len() - 1
+            }
+        };
+        match field {
+            "file" => entries[position].1 = Url::parse(value).ok(),
+            _ => entries[position].2 = Some(value.to_string()).filter(|t| !t.is_empty()),
+        }
+    }
+    entries.sort_by_key(|(index, _, _)| *index);
+    entries
+        .into_iter()
+        .filter_map(|(_, url, title)| Some(Stream::new(url?, title)))
+        .collect()
+}
+
+fn parse_asx(content: &str) -> Vec<Stream> {
+    let lowercase = content.to_ascii_lowercase();
+    let mut streams = vec![];
+    for (start, _) in lowercase.match_indices("<entry") {
+        let end = lowercase[start..]
+            .find("</entry")
+            .map_or(content.len(), |end| start + end);
+        let entry = &content[start..end];
+        let entry_lowercase = &lowercase[start..end];
+
+        let title = element_text(entry, entry_lowercase, "title");
+        for (tag_start, _) in entry_lowercase.match_indices("<ref") {
+            let tag_end = entry_lowercase[tag_start..]
+                .find('>')
+                .map_or(entry.len(), |end| tag_start + end);
+            let href = attribute_value(
+                &entry[tag_start..tag_end],
+                &entry_lowercase[tag_start..tag_end],
+                "href",
+            );
+            if let Some(url) = href.and_then(|href| Url::parse(&href).ok()) {
+                let mut stream = Stream::new(url, title.clone());
+                stream.format = stream.format.or(Some(Format::WMA));
+                streams.push(stream);
+            }
+        }
+    }
+    streams
+}
+
+fn element_text(content: &str, lowercase: &str, name: &str) -> Option<String> {
+    let start = lowercase.find(&format!("<{}", name))?;
+    let start = start + lowercase[start..].find('>')? + 1;
+    let end = start + lowercase[start..].find(&format!("</{}", name))?;
+    Some(unescape(content[start..end].trim())).filter(|t| !t.is_empty())
+}
+
+/// Finds the value of attribute `name` in a start tag, which may have
+/// whitespace around the `=`.
+fn attribute_value(tag: &str, lowercase: &str, name: &str) -> Option<String> {
+    for (start, _) in lowercase.match_indices(name) {
+        let after_boundary = match lowercase[..start].chars().next_back() {
+            Some(c) => c.is_whitespace() || c == '<',
+            None => true,
+        };
+        let rest = tag[start + name.len()..].trim_start();
+        let rest = match rest.strip_prefix('=') {
+            Some(rest) if after_boundary => rest.trim_start(),
+            _ => continue,
+        };
+        let quote = rest.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &rest[1..];
+        let end = value.find(quote)?;
+        return Some(unescape(&value[..end]));
+    }
+    None
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn format_hint(url: &Url) -> Option<Format> {
+    let path = url.path().to_ascii_lowercase();
+    let extension = path.rsplit('/').next()?.rsplit('.').next()?;
+    match extension {
+        "mp3" => Some(Format::MP3),
+        "aac" => Some(Format::AAC),
+        "aacp" => Some(Format::AACPlus),
+        "ogg" | "oga" | "opus" => Some(Format::OGG),
+        "m3u8" => Some(Format::HLS),
+        "flac" => Some(Format::FLAC),
+        "wma" | "asf" => Some(Format::WMA),
+        _ => None,
+    }
+}
+
+/// Finds bitrates written like `128kbps`, `128 kbit/s` or `(64k)`.
+fn bitrate_hint(title: &str) -> Option<u16> {
+    let lowercase = title.to_ascii_lowercase();
+    let mut rest = lowercase.as_str();
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let preceded_by_letter =
+            matches!(rest[..start].chars().next_back(), Some(c) if c.is_ascii_alphabetic());
+        let end = rest[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(rest.len(), |end| start + end);
+        let unit = rest[end..].trim_start();
+        let is_kilobits = ["kbps", "kbit", "kb/s"].iter().any(|u| unit.starts_with(u))
+            || (unit.starts_with('k') && !unit[1..].starts_with(|c: char| c.is_ascii_alphabetic()));
+        if is_kilobits && !preceded_by_letter {
+            if let Ok(bitrate) = rest[start..end].parse() {
+                return Some(bitrate);
+            }
+        }
+        rest = &rest[end..];
+    }
+    None
+}
@@ -0,0 +1,177 @@
+//! Visitors walking an outline tree, and transformations built on them.
+//!
+//! Each `visit_*` method defaults to walking into the children of the visited
+//! node, so implementations only override the methods they care about. Call
+//! the matching `walk_*` function from an override to keep descending.
+//!
+//! ```
+//! use tunein::visit::{walk_outlines_mut, VisitorMut};
+//! use tunein::{Document, Outline};
+//!
+//! /// Replaces every group by its children.
+//! struct Flatten;
+//!
+//! impl VisitorMut for Flatten {
+//!     fn visit_outlines_mut(&mut self, outlines: &mut Vec<Outline>) {
+//!         walk_outlines_mut(self, outlines);
+//!         let mut flat = Vec::with_capacity(outlines.len());
+//!         for outline in outlines.drain(..) {
+//!             match outline {
+//!                 Outline::Group(group) => flat.extend(group.outlines),
+//!                 outline => flat.push(outline),
+//!             }
+//!         }
+//!         *outlines = flat;
+//!     }
+//! }
+//!
+//! let mut document = tunein::read(std::fs::File::open("tests/documents/sample_3.opml")?)?;
+//! Flatten.visit_document_mut(&mut document);
+//! assert!(document.outlines.iter().all(|outline| !matches!(outline, Outline::Group(_))));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::common::{Audio, Document, Group, Link, Object, Outline, Text};
+
+/// Read-only visitor over an outline tree.
+pub trait Visitor {
+    fn visit_document(&mut self, document: &Document) {
+        walk_document(self, document)
+    }
+
+    /// Visits a list of sibling outlines, either top-level or in a group.
+    fn visit_outlines(&mut self, outlines: &[Outline]) {
+        walk_outlines(self, outlines)
+    }
+
+    fn visit_outline(&mut self, outline: &Outline) {
+        walk_outline(self, outline)
+    }
+
+    fn visit_group(&mut self, group: &Group) {
+        walk_group(self, group)
+    }
+
+    fn visit_link(&mut self, _link: &Link) {}
+
+    fn visit_audio(&mut self, _audio: &Audio) {}
+
+    fn visit_text(&mut self, _text: &Text) {}
+
+    fn visit_object(&mut self, _object: &Object) {}
+}
+
+/// Visitor which can modify the outline tree in place.
+pub trait VisitorMut {
+    fn visit_document_mut(&mut self, document: &mut Document) {
+        walk_document_mut(self, document)
+    }
+
+    /// Visits a list of sibling outlines, which may be reordered, removed or
+    /// replaced as a whole.
+    fn visit_outlines_mut(&mut self, outlines: &mut Vec<Outline>) {
+        walk_outlines_mut(self, outlines)
+    }
+
+    fn visit_outline_mut(&mut self, outline: &mut Outline) {
+        walk_outline_mut(self, outline)
+    }
+
+    fn visit_group_mut(&mut self, group: &mut Group) {
+        walk_group_mut(self, group)
+    }
+
+    fn visit_link_mut(&mut self, _link: &mut Link) {}
+
+    fn visit_audio_mut(&mut self, _audio: &mut Audio) {}
+
+    fn visit_text_mut(&mut self, _text: &mut Text) {}
+
+    fn visit_object_mut(&mut self, _object: &mut Object) {}
+}
+
+pub fn walk_document<V: Visitor + ?Sized>(visitor: &mut V, document: &Document) {
+    visitor.visit_outlines(&document.outlines);
+}
+
+pub fn walk_outlines<V: Visitor + ?Sized>(visitor: &mut V, outlines: &[Outline]) {
+    for outline in outlines {
+        visitor.visit_outline(outline);
+    }
+}
+
+pub fn walk_outline<V: Visitor + ?Sized>(visitor: &mut V, outline: &Outline) {
+    match outline {
+        Outline::Group(group) => visitor.visit_group(group),
+        Outline::Link(link) => visitor.visit_link(link),
+        Outline::Audio(audio) => visitor.visit_audio(audio),
+        Outline::Text(text) => visitor.visit_text(text),
+        Outline::Object(object) => visitor.visit_object(object),
+    }
+}
+
+pub fn walk_group<V: Visitor + ?Sized>(visitor: &mut V, group: &Group) {
+    visitor.visit_outlines(&group.outlines);
+}
+
+pub fn walk_document_mut<V: VisitorMut + ?Sized>(visitor: &mut V, document: &mut Document) {
+    visitor.visit_outlines_mut(&mut document.outlines);
+}
+
+pub fn walk_outlines_mut<V: VisitorMut + ?Sized>(visitor: &mut V, outlines: &mut Vec<Outline>) {
+    for outline in outlines {
+        visitor.visit_outline_mut(outline);
+    }
+}
+
+pub fn walk_outline_mut<V: VisitorMut + ?Sized>(visitor: &mut V, outline: &mut Outline) {
+    match outline {
+        Outline::Group(group) => visitor.visit_group_mut(group),
+        Outline::Link(link) => visitor.visit_link_mut(link),
+        Outline::Audio(audio) => visitor.visit_audio_mut(audio),
+        Outline::Text(text) => visitor.visit_text_mut(text),
+        Outline::Object(object) => visitor.visit_object_mut(object),
+    }
+}
+
+pub fn walk_group_mut<V: VisitorMut + ?Sized>(visitor: &mut V, group: &mut Group) {
+    visitor.visit_outlines_mut(&mut group.outlines);
+}
+
+struct Retain<F>(F);
+
+impl<F: FnMut(&Outline) -> bool> VisitorMut for Retain<F> {
+    fn visit_outlines_mut(&mut self, outlines: &mut Vec<Outline>) {
+        outlines.retain(|outline| (self.0)(outline));
+        walk_outlines_mut(self, outlines);
+    }
+}
+
+struct Map<F>(F);
+
+impl<F: FnMut(Outline) -> Outline> VisitorMut for Map<F> {
+    fn visit_outline_mut(&mut self, outline: &mut Outline) {
+        walk_outline_mut(self, outline);
+        let original = std::mem::replace(outline, Outline::Text(Text::default()));
+        *outline = (self.0)(original);
+    }
+}
+
+impl Document {
+    /// Removes the outlines for which `predicate` returns `false`, at any depth.
+    ///
+    /// Outlines are checked parent first, so children of a removed group
+    /// aren't passed to `predicate`.
+    pub fn retain<F: FnMut(&Outline) -> bool>(&mut self, predicate: F) {
+        Retain(predicate).visit_document_mut(self);
+    }
+
+    /// Replaces every outline, at any depth, with the result of `f`.
+    ///
+    /// Outlines are passed children first, so a group reaches `f` with its
+    /// children already mapped.
+    pub fn map<F: FnMut(Outline) -> Outline>(mut self, f: F) -> Document {
+        Map(f).visit_document_mut(&mut self);
+        self
+    }
+}
@@ -0,0 +1,50 @@
+use crate::common::{Document, Outline};
+use crate::error::Error;
+use crate::writer::Writer;
+
+use std::io::Write;
+
+/// Writes a whole document as TuneIn OPML.
+///
+/// Use [`Writer`](crate::Writer) to write the document event by event instead.
+pub fn write<W: Write>(document: &Document, sink: W) -> Result<(), Error> {
+    let mut writer = Writer::new(sink);
+
+    writer.start_document(&document.version)?;
+
+    writer.start_element("head")?;
+    writer.text_element("title", &document.head.title)?;
+    if let Some(status) = document.head.status {
+        writer.text_element("status", &status.to_string())?;
+    }
+    if let Some(fault) = &document.head.fault {
+        writer.text_element("fault", fault)?;
+    }
+    if let Some(code) = &document.head.fault_code {
+        writer.text_element("fault_code", code)?;
+    }
+    writer.end_element()?;
+
+    writer.start_element("body")?;
+    write_outlines(&mut writer, &document.outlines)?;
+    writer.end_element()?;
+
+    writer.end_element()
+}
+
+fn write_outlines<W: Write>(writer: &mut Writer<W>, outlines: &[Outline]) -> Result<(), Error> {
+    for outline in outlines {
+        match outline {
+            Outline::Group(group) => {
+                writer.start_group(&group.text, &group.key, &group.attributes)?;
+                write_outlines(writer, &group.outlines)?;
+            }
+            Outline::Link(link) => writer.start_link(link)?,
+            Outline::Audio(audio) => writer.start_audio(audio)?,
+            Outline::Text(text) => writer.start_text(text)?,
+            Outline::Object(object) => writer.start_object(object)?,
+        }
+        writer.end_element()?;
+    }
+    Ok(())
+}
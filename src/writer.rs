@@ -0,0 +1,285 @@
+use crate::common::{
+    Attributes, Audio, Detail, Format, Item, Link, Object, ProgramDetail, StationDetail, Text,
+    Version,
+};
+use crate::error::Error;
+use crate::event::{Event, OutlineEvent};
+use crate::guide_id::GuideId;
+use std::io::Write;
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
+
+/// Push-based writer emitting TuneIn OPML from a sequence of [`Event`]s.
+///
+/// It is the counterpart of [`Reader`](crate::Reader): feeding it the events
+/// produced by the reader writes an equivalent document.
+///
+/// ```
+/// use tunein::{Event, OutlineEvent, Version, Writer};
+///
+/// let mut writer = Writer::new(Vec::new());
+/// writer.write(&Event::StartDocument { version: Version { major: 1, minor: 0 } })?;
+/// writer.write(&Event::StartBody)?;
+/// writer.write(&Event::StartOutline(Box::new(OutlineEvent::Text("No stations".into()))))?;
+/// writer.write(&Event::EndOutline)?;
+/// writer.write(&Event::EndBody)?;
+/// writer.write(&Event::EndDocument)?;
+///
+/// let document = tunein::read(writer.into_inner().as_slice())?;
+/// assert_eq!(document.outlines, vec![tunein::Outline::Text("No stations".into())]);
+/// # Ok::<(), tunein::Error>(())
+/// ```
+pub struct Writer<W: Write> {
+    writer: EventWriter<W>,
+}
+
+impl<W: Write> Writer<W> {
+    /// Creates a writer emitting indented XML into `sink`.
+    pub fn new(sink: W) -> Writer<W> {
+        Writer {
+            writer: EmitterConfig::new()
+                .perform_indent(true)
+                .create_writer(sink),
+        }
+    }
+
+    /// Writes a single event.
+    pub fn write(&mut self, event: &Event) -> Result<(), Error> {
+        match event {
+            Event::StartDocument { version } => self.start_document(version),
+            Event::EndDocument => self.end_element(),
+            Event::StartHead => self.start_element("head"),
+            Event::EndHead => self.end_element(),
+            Event::StartBody => self.start_element("body"),
+            Event::EndBody => self.end_element(),
+            Event::Title(title) => self.text_element("title", title),
+            Event::Status(Some(status)) => self.text_element("status", &status.to_string()),
+            Event::Status(None) => Ok(()),
+            Event::Fault(fault) => self.text_element("fault", fault),
+            Event::FaultCode(code) => self.text_element("fault_code", code),
+            Event::StartOutline(outline) => match outline.as_ref() {
+                OutlineEvent::Group {
+                    text,
+                    key,
+                    attributes,
+                } => self.start_group(text, key, attributes),
+                OutlineEvent::Link(link) => self.start_link(link),
+                OutlineEvent::Audio(audio) => self.start_audio(audio),
+                OutlineEvent::Text(text) => self.start_text(text),
+                OutlineEvent::Object(object) => self.start_object(object),
+            },
+            Event::EndOutline => self.end_element(),
+        }
+    }
+
+    /// Unwraps the underlying sink.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+
+    pub(crate) fn start_document(&mut self, version: &Version) -> Result<(), Error> {
+        let version = if version.minor == 0 {
+            version.major.to_string()
+        } else {
+            format!("{}.{}", version.major, version.minor)
+        };
+        self.start_element_with("opml", &[("version", version)])
+    }
+
+    pub(crate) fn start_element(&mut self, name: &str) -> Result<(), Error> {
+        self.start_element_with(name, &[])
+    }
+
+    pub(crate) fn end_element(&mut self) -> Result<(), Error> {
+        self.writer.write(XmlEvent::end_element())?;
+        Ok(())
+    }
+
+    pub(crate) fn text_element(&mut self, name: &str, text: &str) -> Result<(), Error> {
+        self.start_element(name)?;
+        self.writer.write(XmlEvent::characters(text))?;
+        self.end_element()
+    }
+
+    pub(crate) fn start_group(
+        &mut self,
+        text: &str,
+        key: &str,
+        extra: &Attributes,
+    ) -> Result<(), Error> {
+        let mut attributes = vec![];
+        push_str(&mut attributes, "text", text);
+        push_str(&mut attributes, "key", key);
+        push_extra(&mut attributes, extra);
+        self.start_element_with("outline", &attributes)
+    }
+
+    pub(crate) fn start_link(&mut self, link: &Link) -> Result<(), Error> {
+        let mut attributes = vec![("type", "link".to_string())];
+        push_str(&mut attributes, "text", &link.text);
+        push_str(&mut attributes, "URL", &link.url);
+        push_str(&mut attributes, "key", &link.key);
+        push_guide_id(&mut attributes, "guide_id", &link.guide_id);
+        push_item(&mut attributes, &link.item);
+        push_extra(&mut attributes, &link.attributes);
+        self.start_element_with("outline", &attributes)
+    }
+
+    pub(crate) fn start_audio(&mut self, audio: &Audio) -> Result<(), Error> {
+        let mut attributes = vec![("type", "audio".to_string())];
+        push_str(&mut attributes, "text", &audio.text);
+        push_str(&mut attributes, "URL", &audio.url);
+        push_number(&mut attributes, "bitrate", audio.bitrate);
+        push_number(&mut attributes, "reliability", audio.reliability);
+        push_guide_id(&mut attributes, "guide_id", &audio.guide_id);
+        push_str(&mut attributes, "subtext", &audio.subtext);
+        push_guide_id(&mut attributes, "genre_id", &audio.genre_id);
+        push_str(
+            &mut attributes,
+            "formats",
+            &Format::join_list(&audio.formats),
+        );
+        push_item(&mut attributes, &audio.item);
+        push_str(&mut attributes, "image", &audio.image);
+        push_guide_id(&mut attributes, "now_playing_id", &audio.now_playing_id);
+        push_guide_id(&mut attributes, "preset_id", &audio.preset_id);
+        push_extra(&mut attributes, &audio.attributes);
+        self.start_element_with("outline", &attributes)
+    }
+
+    pub(crate) fn start_text(&mut self, text: &Text) -> Result<(), Error> {
+        let mut attributes = vec![("type", "text".to_string()), ("text", text.text.clone())];
+        push_extra(&mut attributes, &text.attributes);
+        self.start_element_with("outline", &attributes)
+    }
+
+    /// Starts an object outline and writes its whole detail element.
+    pub(crate) fn start_object(&mut self, object: &Object) -> Result<(), Error> {
+        let mut attributes = vec![("type", "object".to_string())];
+        push_str(&mut attributes, "text", &object.text);
+        push_extra(&mut attributes, &object.attributes);
+        self.start_element_with("outline", &attributes)?;
+        match &object.detail {
+            Some(detail) => self.detail(detail),
+            None => Ok(()),
+        }
+    }
+
+    fn detail(&mut self, detail: &Detail) -> Result<(), Error> {
+        let (name, fields) = match detail {
+            Detail::Station(station) => ("station", station_fields(station)),
+            Detail::Program(program) => ("program", program_fields(program)),
+            Detail::Other { name, fields } => {
+                let mut children = vec![];
+                push_extra(&mut children, fields);
+                (name.as_str(), children)
+            }
+        };
+        self.start_element(name)?;
+        for (name, value) in fields {
+            self.text_element(name, &value)?;
+        }
+        self.end_element()
+    }
+
+    fn start_element_with(
+        &mut self,
+        name: &str,
+        attributes: &[(&str, String)],
+    ) -> Result<(), Error> {
+        let element = attributes
+            .iter()
+            .fold(XmlEvent::start_element(name), |element, (name, value)| {
+                element.attr(*name, value)
+            });
+        self.writer.write(element)?;
+        Ok(())
+    }
+}
+
+fn push_str<'a>(attributes: &mut Vec<(&'a str, String)>, name: &'a str, value: &str) {
+    if !value.is_empty() {
+        attributes.push((name, value.to_string()));
+    }
+}
+
+fn push_guide_id<'a>(
+    attributes: &mut Vec<(&'a str, String)>,
+    name: &'a str,
+    value: &Option<GuideId>,
+) {
+    if let Some(value) = value {
+        attributes.push((name, value.to_string()));
+    }
+}
+
+fn push_item(attributes: &mut Vec<(&str, String)>, value: &Option<Item>) {
+    if let Some(value) = value {
+        attributes.push(("item", value.to_string()));
+    }
+}
+
+/// Pushes the extra attributes, skipping the ones already pushed so the
+/// element doesn't get duplicate attributes.
+fn push_extra<'a>(attributes: &mut Vec<(&'a str, String)>, extra: &'a Attributes) {
+    for (name, value) in extra.iter() {
+        if !attributes.iter().any(|(pushed, _)| *pushed == name) {
+            attributes.push((name, value.to_string()));
+        }
+    }
+}
+
+fn push_number<'a>(attributes: &mut Vec<(&'a str, String)>, name: &'a str, value: Option<u16>) {
+    if let Some(value) = value {
+        attributes.push((name, value.to_string()));
+    }
+}
+
+fn push_bool<'a>(attributes: &mut Vec<(&'a str, String)>, name: &'a str, value: bool) {
+    attributes.push((name, value.to_string()));
+}
+
+fn station_fields(station: &StationDetail) -> Vec<(&str, String)> {
+    let mut fields = vec![];
+    push_guide_id(&mut fields, "guide_id", &station.guide_id);
+    push_guide_id(&mut fields, "preset_id", &station.preset_id);
+    push_str(&mut fields, "name", &station.name);
+    push_str(&mut fields, "call_sign", &station.call_sign);
+    push_str(&mut fields, "slogan", &station.slogan);
+    push_str(&mut fields, "description", &station.description);
+    push_str(&mut fields, "frequency", &station.frequency);
+    push_str(&mut fields, "band", &station.band);
+    push_str(&mut fields, "url", &station.url);
+    push_str(&mut fields, "logo", &station.logo);
+    push_str(&mut fields, "location", &station.location);
+    push_guide_id(&mut fields, "genre_id", &station.genre_id);
+    push_str(&mut fields, "genre_name", &station.genre_name);
+    push_guide_id(&mut fields, "region_id", &station.region_id);
+    push_str(&mut fields, "language", &station.language);
+    push_str(&mut fields, "twitter_id", &station.twitter_id);
+    push_bool(&mut fields, "is_available", station.is_available);
+    push_bool(&mut fields, "is_music", station.is_music);
+    push_bool(&mut fields, "has_song", station.has_song);
+    push_bool(&mut fields, "has_schedule", station.has_schedule);
+    push_bool(&mut fields, "has_topics", station.has_topics);
+    push_extra(&mut fields, &station.fields);
+    fields
+}
+
+fn program_fields(program: &ProgramDetail) -> Vec<(&str, String)> {
+    let mut fields = vec![];
+    push_guide_id(&mut fields, "guide_id", &program.guide_id);
+    push_str(&mut fields, "name", &program.name);
+    push_str(&mut fields, "description", &program.description);
+    push_str(&mut fields, "hosts", &program.hosts);
+    push_str(&mut fields, "url", &program.url);
+    push_str(&mut fields, "logo", &program.logo);
+    push_str(&mut fields, "location", &program.location);
+    push_guide_id(&mut fields, "genre_id", &program.genre_id);
+    push_str(&mut fields, "genre_name", &program.genre_name);
+    push_str(&mut fields, "language", &program.language);
+    push_str(&mut fields, "twitter_id", &program.twitter_id);
+    push_bool(&mut fields, "is_available", program.is_available);
+    push_bool(&mut fields, "has_topics", program.has_topics);
+    push_extra(&mut fields, &program.fields);
+    fields
+}
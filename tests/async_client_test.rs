@@ -0,0 +1,249 @@
+#![cfg(feature = "async")]
+
+use std::collections::HashMap;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::AsyncRead;
+use tunein::async_client::{AsyncClient, AsyncTransport, BoxFuture};
+use tunein::cache::CachingTransport;
+use tunein::client::Response;
+use tunein::policy::Policy;
+use tunein::request::Request;
+use tunein::{ErrorKind, Url};
+
+/// Source yielding a few bytes at a time, then `end` once they run out.
+struct Trickle {
+    content: Vec<u8>,
+    offset: usize,
+    end: Poll<io::Result<usize>>,
+}
+
+impl Trickle {
+    fn new(path: &str, end: Poll<io::Result<usize>>) -> Trickle {
+        Trickle {
+            content: std::fs::read(path).unwrap(),
+            offset: 0,
+            end,
+        }
+    }
+}
+
+impl AsyncRead for Trickle {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _context: &mut Context<'_>,
+        buffer: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let rest = &self.content[self.offset..];
+        if rest.is_empty() {
+            return match &self.end {
+                Poll::Ready(Ok(length)) => Poll::Ready(Ok(*length)),
+                Poll::Ready(Err(error)) => Poll::Ready(Err(io::Error::new(error.kind(), "Broken"))),
+                Poll::Pending => Poll::Pending,
+            };
+        }
+        let length = rest.len().min(buffer.len()).min(7);
+        buffer[..length].copy_from_slice(&rest[..length]);
+        self.offset += length;
+        Poll::Ready(Ok(length))
+    }
+}
+
+struct FixtureTransport {
+    routes: HashMap<&'static str, &'static str>,
+    /// Statuses of the routes which don't answer with 200.
+    statuses: HashMap<&'static str, u16>,
+}
+
+impl AsyncTransport for FixtureTransport {
+    type Body = io::Cursor<Vec<u8>>;
+
+    fn get<'a>(&'a self, url: &'a Url) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        Box::pin(async move {
+            let path = self
+                .routes
+                .get(url.as_str())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, url.to_string()))?;
+            Ok(Response {
+                status: self.statuses.get(url.as_str()).copied().unwrap_or(200),
+                headers: vec![],
+                body: io::Cursor::new(std::fs::read(path)?),
+            })
+        })
+    }
+}
+
+fn transport() -> FixtureTransport {
+    let mut routes = HashMap::new();
+    routes.insert(
+        "http://opml.radiotime.com/Browse.ashx",
+        "tests/documents/sample_1.opml",
+    );
+    routes.insert(
+        "http://opml.radiotime.com/Search.ashx?query=Krak%C3%B3w",
+        "tests/documents/sample_2.opml",
+    );
+    routes.insert(
+        "http://opml.radiotime.com/Tune.ashx?id=s16527",
+        "tests/documents/tune_2.pls",
+    );
+    routes.insert(
+        "http://opml.radiotime.com/Browse.ashx?id=g19",
+        "tests/documents/paged_1.opml",
+    );
+    routes.insert(
+        "http://opml.radiotime.com/Browse.ashx?id=g19&offset=2",
+        "tests/documents/paged_2.opml",
+    );
+    routes.insert(
+        "http://opml.radiotime.com/Browse.ashx?id=g19&offset=4",
+        "tests/documents/paged_3.opml",
+    );
+    routes.insert(
+        "http://opml.radiotime.com/Browse.ashx?id=g19&filter=p&offset=1",
+        "tests/documents/paged_4.opml",
+    );
+    routes.insert(
+        "http://opml.radiotime.com/Describe.ashx?id=s1",
+        "tests/documents/fault_1.opml",
+    );
+    routes.insert(
+        "http://opml.radiotime.com/Describe.ashx?id=s2",
+        "tests/documents/tune_1.m3u",
+    );
+    let mut statuses = HashMap::new();
+    statuses.insert("http://opml.radiotime.com/Describe.ashx?id=s1", 400);
+    statuses.insert("http://opml.radiotime.com/Describe.ashx?id=s2", 502);
+    FixtureTransport { routes, statuses }
+}
+
+fn client() -> AsyncClient<FixtureTransport> {
+    AsyncClient::new(transport())
+}
+
+#[tokio::test]
+async fn browse() {
+    let document = client().browse().await.unwrap();
+    assert_eq!(document.head.title, "Browse");
+}
+
+#[tokio::test]
+async fn search() {
+    let document = client().search("Kraków").await.unwrap();
+    assert_eq!(document.head.title, "Kraków");
+}
+
+#[tokio::test]
+async fn not_found() {
+    let error = client().browse_category("music").await.unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Io(_)));
+}
+
+#[tokio::test]
+async fn fault_with_error_status() {
+    let error = client().describe(&"s1".parse().unwrap()).await.unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Fault(fault) if fault.status == 400));
+
+    let error = client().describe(&"s2".parse().unwrap()).await.unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        ErrorKind::HttpStatus { status: 502 }
+    ));
+}
+
+#[tokio::test]
+async fn read_async() {
+    let file = tokio::fs::File::open("tests/documents/sample_3.opml")
+        .await
+        .unwrap();
+    let document = tunein::read_async(file).await.unwrap();
+    let expected = tunein::read(std::fs::File::open("tests/documents/sample_3.opml").unwrap());
+    assert_eq!(document, expected.unwrap());
+}
+
+#[tokio::test]
+async fn read_async_incrementally() {
+    let path = "tests/documents/sample_2.opml";
+    let expected = tunein::read(std::fs::File::open(path).unwrap()).unwrap();
+
+    let document = tunein::read_async(Trickle::new(path, Poll::Ready(Ok(0))))
+        .await
+        .unwrap();
+    assert_eq!(document, expected);
+
+    // The document is complete before the source ends.
+    let document = tunein::read_async(Trickle::new(path, Poll::Pending))
+        .await
+        .unwrap();
+    assert_eq!(document, expected);
+
+    let broken = Poll::Ready(Err(io::Error::new(io::ErrorKind::ConnectionReset, "")));
+    let mut source = Trickle::new(path, broken);
+    source.content.truncate(200);
+    let error = tunein::read_async(source).await.unwrap_err();
+    // xml-rs reports errors of the source as its own.
+    assert!(matches!(error.kind(), ErrorKind::XmlError(_)), "{}", error);
+}
+
+#[tokio::test]
+async fn resolve() {
+    let url = Url::parse("http://opml.radiotime.com/Tune.ashx?id=s16527").unwrap();
+    let streams = client().resolve(&url).await.unwrap();
+    assert_eq!(streams.len(), 2);
+    assert_eq!(
+        streams[0].url.as_str(),
+        "http://stream.rcs.revma.com/krkfm.aac"
+    );
+}
+
+#[tokio::test]
+async fn pages() {
+    let client = client();
+    let request = Request::browse().id(&"g19".parse().unwrap());
+
+    let mut pages = client.pages(&request).limit(3);
+    let mut count = 0;
+    while let Some(page) = pages.next().await {
+        page.unwrap();
+        count += 1;
+    }
+    assert_eq!(count, 3);
+    assert!(pages.truncated());
+
+    let document = client.pages(&request).merge().await.unwrap();
+    assert_eq!(document.audios().count(), 5);
+    assert_eq!(document.links().count(), 2);
+}
+
+#[tokio::test]
+async fn cache() {
+    let client = AsyncClient::new(CachingTransport::new(transport()));
+    let document = client.browse().await.unwrap();
+    assert_eq!(client.browse().await.unwrap(), document);
+
+    let error = client.browse_category("music").await.unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Io(_)));
+}
+
+struct SlowTransport;
+
+impl AsyncTransport for SlowTransport {
+    type Body = io::Cursor<Vec<u8>>;
+
+    fn get<'a>(&'a self, _url: &'a Url) -> BoxFuture<'a, io::Result<Response<Self::Body>>> {
+        Box::pin(async move {
+            tokio::time::delay_for(Duration::from_secs(5)).await;
+            Err(io::Error::new(io::ErrorKind::TimedOut, "too slow"))
+        })
+    }
+}
+
+#[tokio::test]
+async fn timeout() {
+    let policy = Policy::new().retries(3).timeout(Duration::from_millis(20));
+    let client = AsyncClient::new(SlowTransport).with_policy(policy);
+    let error = client.browse().await.unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Timeout));
+}
@@ -0,0 +1,71 @@
+use tunein::{Attributes, Outline};
+
+const INPUT: &str = r#"<opml version="1"><body>
+<outline text="Stations" key="stations" topic_duration="60">
+<outline type="audio" text="Anty Radio" bitrate="96" playing="Queen - Bicycle" playing_image="http://example.com/queen.png" is_preset="false"/>
+<outline type="link" text="More" key="nextStations" stream_type="live"/>
+</outline>
+<outline type="text" text="No shows" current_track="None"/>
+</body></opml>"#;
+
+#[test]
+fn unknown_attributes() {
+    let document = tunein::read(INPUT.as_bytes()).unwrap();
+
+    let group = match &document.outlines[0] {
+        Outline::Group(group) => group,
+        outline => panic!("Unexpected outline {:?}", outline),
+    };
+    assert_eq!(group.attribute("topic_duration"), Some("60"));
+    assert_eq!(group.attribute("text"), None);
+
+    match &group.outlines[0] {
+        Outline::Audio(audio) => {
+            assert_eq!(audio.bitrate, Some(96));
+            let attributes: Vec<_> = audio.attributes.iter().collect();
+            assert_eq!(
+                attributes,
+                vec![
+                    ("playing", "Queen - Bicycle"),
+                    ("playing_image", "http://example.com/queen.png"),
+                    ("is_preset", "false"),
+                ]
+            );
+        }
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+
+    match &group.outlines[1] {
+        Outline::Link(link) => {
+            assert_eq!(link.attribute("stream_type"), Some("live"));
+            assert_eq!(link.attributes.len(), 1);
+        }
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+
+    match &document.outlines[1] {
+        Outline::Text(text) => assert_eq!(text.attribute("current_track"), Some("None")),
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+}
+
+#[test]
+fn round_trip() {
+    let document = tunein::read(INPUT.as_bytes()).unwrap();
+
+    let mut buffer = Vec::new();
+    tunein::write(&document, &mut buffer).unwrap();
+
+    assert_eq!(tunein::read(buffer.as_slice()).unwrap(), document);
+}
+
+#[test]
+fn insert_keeps_order() {
+    let mut attributes: Attributes = vec![("a", "1"), ("b", "2")].into_iter().collect();
+    attributes.insert("a", "3");
+    attributes.insert("c", "4");
+    assert_eq!(attributes.remove("b"), Some("2".to_string()));
+
+    let attributes: Vec<_> = attributes.iter().collect();
+    assert_eq!(attributes, vec![("a", "3"), ("c", "4")]);
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const FIXTURE: &str = "tests/documents/browser_1.opml";
+
+/// Screen after typing `keys` in the interactive browser.
+fn screen(keys: &[&str]) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tunein"))
+        .args(["browse", "--interactive", FIXTURE])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    for key in keys {
+        writeln!(stdin, "{}", key).unwrap();
+    }
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let screen = String::from_utf8(output.stdout).unwrap();
+    screen.lines().map(str::to_string).collect()
+}
+
+/// List and details columns of the body rows.
+fn columns(lines: &[String]) -> (Vec<&str>, Vec<&str>) {
+    lines[2..lines.len() - 1]
+        .iter()
+        .map(|line| match line.split_once(" |") {
+            Some((list, details)) => (list.trim_end(), details.trim()),
+            None => (line.as_str(), ""),
+        })
+        .unzip()
+}
+
+#[test]
+fn initial_screen() {
+    let lines = screen(&[]);
+    assert_eq!(lines.len(), 20);
+    assert_eq!(lines[0], "Fixtures");
+    let (list, details) = columns(&lines);
+    assert_eq!(
+        &list[..5],
+        &[
+            "> Places:",
+            "    Angola /",
+            "    Kraków /",
+            "  KRK.FM 102.4",
+            "  Missing /"
+        ]
+    );
+    assert_eq!(&details[..3], &["Places", "", "Outlines: 2"]);
+    assert_eq!(
+        lines[19],
+        "Enter: open  Backspace: back  /: search  q: quit"
+    );
+}
+
+#[test]
+fn audio_details() {
+    let lines = screen(&["down", "down", "down"]);
+    let (list, details) = columns(&lines);
+    assert_eq!(list[3], "> KRK.FM 102.4");
+    for line in &[
+        "Subtext: Polska",
+        "Bitrate: 128 kbps",
+        "Reliability: 99%",
+        "Format: mp3, aac",
+    ] {
+        assert!(details.contains(line), "{:?} in {:?}", line, details);
+    }
+    let details = details.concat();
+    assert!(details.contains("Image: http://cdn-radiotime-logos.tunein.com/s16527q.png"));
+}
+
+#[test]
+fn follow_and_back() {
+    let lines = screen(&["down", "enter"]);
+    assert_eq!(lines[0], "Fixtures > Angola");
+    let (list, _) = columns(&lines);
+    assert_eq!(list[1], "    L.A.C FM 95.5 (Portuguese Music)");
+
+    let lines = screen(&["down", "enter", "backspace"]);
+    assert_eq!(lines[0], "Fixtures");
+    let (list, _) = columns(&lines);
+    assert_eq!(list[1], ">   Angola /");
+
+    let lines = screen(&["backspace"]);
+    assert_eq!(lines[19], "Already at the top");
+}
+
+#[test]
+fn failed_link() {
+    let lines = screen(&["down", "down", "down", "down", "enter"]);
+    assert_eq!(lines[0], "Fixtures");
+    assert!(lines[19].contains("missing.opml"));
+}
+
+#[test]
+fn search() {
+    let lines = screen(&["/krk"]);
+    let (list, _) = columns(&lines);
+    assert_eq!(list[0], "> KRK.FM 102.4");
+    assert_eq!(list[1], "");
+    assert_eq!(lines[19], "/krk");
+
+    let lines = screen(&["/kra", "enter", "down"]);
+    let (list, _) = columns(&lines);
+    assert_eq!(&list[..2], &["  Places:", ">   Kraków /"]);
+    assert_eq!(lines[19], "Search: kra  Esc: clear");
+
+    let lines = screen(&["/kra", "enter", "esc"]);
+    let (list, _) = columns(&lines);
+    assert_eq!(list[4], "  Missing /");
+}
@@ -0,0 +1,178 @@
+mod support;
+
+use std::io;
+use std::time::Duration;
+use support::ScriptedTransport;
+use tunein::cache::{CachingTransport, STALE_WARNING};
+use tunein::client::{Client, Transport};
+use tunein::{ErrorKind, Url};
+
+const BROWSE: &str = "tests/documents/sample_1.opml";
+
+fn url(url: &str) -> Url {
+    Url::parse(url).unwrap()
+}
+
+#[test]
+fn fresh() {
+    let transport = CachingTransport::new(ScriptedTransport::new().file(BROWSE));
+    let first = url("http://opml.radiotime.com/Browse.ashx?c=music&formats=mp3#top");
+    let second = url("http://opml.radiotime.com/Browse.ashx?formats=mp3&c=music");
+
+    let client = Client::new(transport);
+    let document = client.fetch(&first).unwrap();
+    assert_eq!(client.fetch(&second).unwrap(), document);
+    assert_eq!(client.transport().inner().requests().len(), 1);
+}
+
+#[test]
+fn revalidate() {
+    let inner = ScriptedTransport::new()
+        .respond(
+            200,
+            &[
+                ("ETag", "\"v1\""),
+                ("Last-Modified", "Mon, 05 Oct 2020 10:00:00 GMT"),
+            ],
+            std::fs::read(BROWSE).unwrap(),
+        )
+        .respond(304, &[], "");
+    let transport = CachingTransport::new(inner).ttl("browse.ashx", Duration::from_secs(0));
+    let client = Client::new(transport);
+
+    let document = client.browse().unwrap();
+    assert_eq!(client.browse().unwrap(), document);
+
+    let requests = client.transport().inner().requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].1.is_empty());
+    assert_eq!(
+        requests[1].1,
+        vec![
+            ("If-None-Match".to_string(), "\"v1\"".to_string()),
+            (
+                "If-Modified-Since".to_string(),
+                "Mon, 05 Oct 2020 10:00:00 GMT".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn stale_while_error() {
+    let inner = ScriptedTransport::new()
+        .file(BROWSE)
+        .fail(io::ErrorKind::ConnectionRefused)
+        .respond(503, &[], "")
+        .respond(429, &[], "")
+        .file("tests/documents/fault_3.opml")
+        .file("tests/documents/fault_2.opml");
+    let transport = CachingTransport::new(inner).default_ttl(Duration::from_secs(0));
+    let browse = url("http://opml.radiotime.com/Browse.ashx");
+
+    let good = transport.get(&browse).unwrap();
+    assert_eq!(good.header("Warning"), None);
+    for _ in 0..5 {
+        let stale = transport.get(&browse).unwrap();
+        assert_eq!(stale.status, 200);
+        assert_eq!(stale.header("Warning"), Some(STALE_WARNING));
+        assert_eq!(stale.body.into_inner(), std::fs::read(BROWSE).unwrap());
+    }
+    assert_eq!(transport.inner().requests().len(), 6);
+}
+
+#[test]
+fn errors_passed_through() {
+    let fault = std::fs::read("tests/documents/fault_1.opml").unwrap();
+    let inner = ScriptedTransport::new()
+        .file(BROWSE)
+        .respond(404, &[], "Not found")
+        .respond(400, &[], fault.clone())
+        .respond(200, &[], fault.clone())
+        .respond(304, &[], "");
+    let transport = CachingTransport::new(inner).default_ttl(Duration::from_secs(0));
+    let browse = url("http://opml.radiotime.com/Browse.ashx");
+
+    transport.get(&browse).unwrap();
+    let expected: [(u16, &[u8]); 3] = [(404, b"Not found"), (400, &fault), (200, &fault)];
+    for (status, body) in expected.iter() {
+        let response = transport.get(&browse).unwrap();
+        assert_eq!(response.status, *status);
+        assert_eq!(response.header("Warning"), None);
+        assert_eq!(response.body.into_inner(), *body);
+    }
+
+    // The faults weren't cached in place of the good body.
+    let response = transport.get(&browse).unwrap();
+    assert_eq!(response.body.into_inner(), std::fs::read(BROWSE).unwrap());
+    assert_eq!(transport.inner().requests().len(), 5);
+}
+
+#[test]
+fn error_without_entry() {
+    let inner = ScriptedTransport::new().fail(io::ErrorKind::ConnectionRefused);
+    let client = Client::new(CachingTransport::new(inner));
+    let error = client.browse().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Io(_)));
+}
+
+#[test]
+fn tune_is_revalidated() {
+    let inner = ScriptedTransport::new()
+        .file("tests/documents/tune_1.m3u")
+        .file("tests/documents/tune_1.m3u");
+    let transport = CachingTransport::new(inner);
+    let tune = url("http://opml.radiotime.com/Tune.ashx?id=s6");
+
+    transport.get(&tune).unwrap();
+    transport.get(&tune).unwrap();
+    assert_eq!(transport.inner().requests().len(), 2);
+}
+
+#[test]
+fn least_recently_used() {
+    let inner = ScriptedTransport::new()
+        .file(BROWSE)
+        .file(BROWSE)
+        .file(BROWSE)
+        .file(BROWSE);
+    let transport = CachingTransport::new(inner).capacity(2);
+    let a = url("http://opml.radiotime.com/Browse.ashx?c=a");
+    let b = url("http://opml.radiotime.com/Browse.ashx?c=b");
+    let c = url("http://opml.radiotime.com/Browse.ashx?c=c");
+
+    for url in &[&a, &b, &a, &c, &a, &b] {
+        transport.get(url).unwrap();
+    }
+    assert_eq!(
+        transport.inner().urls(),
+        vec![a.as_str(), b.as_str(), c.as_str(), b.as_str()]
+    );
+}
+
+#[test]
+fn directory() {
+    let directory = std::env::temp_dir().join(format!("tunein-cache-test-{}", std::process::id()));
+    let browse = url("http://opml.radiotime.com/Browse.ashx");
+
+    let first = CachingTransport::new(ScriptedTransport::new().respond(
+        200,
+        &[("ETag", "\"v1\"")],
+        std::fs::read(BROWSE).unwrap(),
+    ))
+    .directory(&directory)
+    .unwrap();
+    first.get(&browse).unwrap();
+
+    let second = CachingTransport::new(ScriptedTransport::new())
+        .directory(&directory)
+        .unwrap();
+    let response = second.get(&browse).unwrap();
+    assert_eq!(response.header("ETag"), Some("\"v1\""));
+    assert_eq!(response.body.into_inner(), std::fs::read(BROWSE).unwrap());
+    assert!(second.inner().requests().is_empty());
+
+    second.clear().unwrap();
+    assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 0);
+    std::fs::remove_dir(&directory).unwrap();
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+
+fn tunein(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tunein"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(args: &[&str]) -> String {
+    let output = tunein(args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn browse_tree() {
+    let output = stdout(&["browse", "tests/documents/sample_3.opml"]);
+    assert_eq!(
+        output,
+        "Angola\n\
+         -----\n\
+         All Stations:\n    \
+         L.A.C FM 95.5 (Portuguese Music) [mp3, 128 kbps, 91%] - http://opml.radiotime.com/Tune.ashx?id=s6228\n\
+         Luanda - http://opml.radiotime.com/Browse.ashx?id=r100526\n"
+    );
+}
+
+#[test]
+fn browse_tsv() {
+    let output = stdout(&["browse", "tests/documents/sample_3.opml", "--output=tsv"]);
+    let rows: Vec<Vec<&str>> = output
+        .lines()
+        .map(|line| line.split('\t').collect())
+        .collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0][1], "type");
+    assert_eq!(
+        rows[2],
+        vec![
+            "1",
+            "audio",
+            "L.A.C FM 95.5 (Portuguese Music)",
+            "s6228",
+            "mp3",
+            "128",
+            "91",
+            "http://opml.radiotime.com/Tune.ashx?id=s6228"
+        ]
+    );
+}
+
+#[test]
+fn browse_json() {
+    let output = stdout(&["browse", "tests/documents/sample_1.json", "-o", "json"]);
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value["head"]["title"], "Browse");
+}
+
+#[test]
+fn filters() {
+    for args in &[
+        ["--min-bitrate", "256"],
+        ["--min-reliability", "95"],
+        ["--format", "aac,ogg"],
+    ] {
+        let output = stdout(&["browse", "tests/documents/sample_3.opml", args[0], args[1]]);
+        assert!(!output.contains("L.A.C FM"), "{:?}", args);
+    }
+    let output = stdout(&[
+        "browse",
+        "tests/documents/sample_3.opml",
+        "-f",
+        "mp3",
+        "--min-bitrate",
+        "128",
+        "--min-reliability",
+        "90",
+    ]);
+    assert!(output.contains("L.A.C FM"));
+}
+
+#[test]
+fn search_file() {
+    let output = stdout(&["search", "tests/documents/sample_3.opml"]);
+    assert!(output.contains("L.A.C FM"));
+}
+
+#[test]
+fn describe() {
+    let output = stdout(&["describe", "tests/documents/describe_1.opml"]);
+    assert!(output.contains("    Slogan: Polska\n"));
+    assert!(output.contains("    Location: Kraków, Poland\n"));
+}
+
+#[test]
+fn export() {
+    let output = stdout(&["export", "tests/documents/sample_3.opml"]);
+    let document = tunein::read(output.as_bytes()).unwrap();
+    let expected = tunein::read(std::fs::File::open("tests/documents/sample_3.opml").unwrap());
+    assert_eq!(document, expected.unwrap());
+}
+
+#[test]
+fn export_playlist() {
+    let output = stdout(&["export", "tests/documents/playlist_1.opml", "-p", "m3u"]);
+    let streams = tunein::tune::parse_playlist(&output);
+    assert_eq!(streams.len(), 2);
+    assert_eq!(streams[0].title.as_deref(), Some("KRK.FM 102.4 - Polska"));
+
+    let output = stdout(&[
+        "export",
+        "tests/documents/playlist_1.opml",
+        "--playlist=xspf",
+    ]);
+    assert!(output.contains("<annotation>Polska</annotation>"));
+}
+
+#[test]
+fn fault() {
+    let output = tunein(&["browse", "tests/documents/fault_1.opml"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Fault 400"));
+}
+
+#[test]
+fn usage_errors() {
+    for args in &[
+        &["bogus"][..],
+        &["describe"],
+        &["describe", "not an id"],
+        &["browse", "--output", "xml"],
+        &["browse", "--format", "mp3,wav"],
+        &["browse", "--min-bitrate"],
+        &["browse", "--colour"],
+        &["browse", "--playlist", "m3u"],
+        &["export", "--playlist", "wav"],
+        &["export", "--resolve"],
+    ] {
+        let output = tunein(args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Usage: tunein"));
+    }
+    assert!(stdout(&["--help"]).starts_with("Usage: tunein"));
+}
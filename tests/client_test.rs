@@ -0,0 +1,88 @@
+mod support;
+
+use support::FixtureTransport;
+use tunein::client::Client;
+use tunein::{ErrorKind, Outline, Url};
+
+fn transport() -> FixtureTransport {
+    FixtureTransport::new()
+        .file(
+            "http://opml.radiotime.com/Browse.ashx",
+            "tests/documents/sample_1.opml",
+        )
+        .file(
+            "http://opml.radiotime.com/Browse.ashx?id=r100526",
+            "tests/documents/sample_3.opml",
+        )
+        .file(
+            "http://opml.radiotime.com/Search.ashx?query=Krak%C3%B3w",
+            "tests/documents/sample_2.opml",
+        )
+        .body("http://opml.radiotime.com/Browse.ashx?c=music", 503, "")
+}
+
+#[test]
+fn browse() {
+    let client = Client::new(transport());
+    let document = client.browse().unwrap();
+    assert_eq!(document.head.title, "Browse");
+    assert_eq!(document.outlines.len(), 7);
+}
+
+#[test]
+fn browse_id() {
+    let client = Client::new(transport());
+    let document = client.browse_id(&"r100526".parse().unwrap()).unwrap();
+    assert_eq!(document.head.title, "Angola");
+}
+
+#[test]
+fn search() {
+    let client = Client::new(transport());
+    let document = client.search("Kraków").unwrap();
+    assert_eq!(document.head.title, "Kraków");
+}
+
+#[test]
+fn fetch_link() {
+    let client = Client::new(transport());
+    let root = client.browse().unwrap();
+    let url = match &root.outlines[4] {
+        Outline::Link(link) => Url::parse(&link.url).unwrap(),
+        outline => panic!("Unexpected outline {:?}", outline),
+    };
+    let error = client.fetch(&url).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Io(_)));
+    assert_eq!(
+        client.transport().requests(),
+        vec![
+            "http://opml.radiotime.com/Browse.ashx",
+            "http://opml.radiotime.com/Browse.ashx?id=r0",
+        ]
+    );
+}
+
+#[test]
+fn http_status() {
+    let client = Client::new(transport());
+    let error = client.browse_category("music").unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        ErrorKind::HttpStatus { status: 503 }
+    ));
+    assert_eq!(error.to_string(), "HTTP status 503");
+}
+
+#[test]
+fn base() {
+    let transport = FixtureTransport::new().file(
+        "http://localhost/tunein/Describe.ashx?id=s6228",
+        "tests/documents/sample_3.opml",
+    );
+    let client =
+        Client::with_base(transport, Url::parse("http://localhost/tunein/").unwrap()).unwrap();
+    assert!(client.describe(&"s6228".parse().unwrap()).is_ok());
+
+    let base = Url::parse("mailto:tunein@example.com").unwrap();
+    assert!(Client::with_base(FixtureTransport::new(), base).is_err());
+}
@@ -0,0 +1,188 @@
+mod support;
+
+use support::FixtureTransport;
+use tunein::client::Client;
+use tunein::crawl::{Crawler, Progress};
+use tunein::request::Request;
+use tunein::{ErrorKind, FaultKind, Group, Outline};
+
+const ROOT: &str = "http://opml.radiotime.com/Browse.ashx";
+const LOCAL: &str = "http://opml.radiotime.com/Browse.ashx?c=local";
+const MUSIC: &str = "http://opml.radiotime.com/Browse.ashx?c=music";
+const LANG: &str = "http://opml.radiotime.com/Browse.ashx?c=lang";
+
+fn transport() -> FixtureTransport {
+    FixtureTransport::new()
+        .file(ROOT, "tests/documents/sample_1.opml")
+        .file(LOCAL, "tests/documents/crawl_1.opml")
+        .file(MUSIC, "tests/documents/crawl_2.opml")
+        .file(
+            "http://opml.radiotime.com/Browse.ashx?id=g19",
+            "tests/documents/paged_1.opml",
+        )
+        .file(
+            "http://opml.radiotime.com/Browse.ashx?id=g19&offset=2",
+            "tests/documents/paged_2.opml",
+        )
+        .file(
+            "http://opml.radiotime.com/Browse.ashx?id=g19&offset=4",
+            "tests/documents/paged_3.opml",
+        )
+        .file(
+            "http://opml.radiotime.com/Browse.ashx?id=g19&filter=p&offset=1",
+            "tests/documents/paged_4.opml",
+        )
+}
+
+fn group(outline: &Outline) -> &Group {
+    match outline {
+        Outline::Group(group) => group,
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+}
+
+fn is_fixture(key: &str) -> bool {
+    key.is_empty() || key == "local" || key == "music"
+}
+
+#[test]
+fn crawl() {
+    let client = Client::new(transport());
+    let crawl = Crawler::new(&client)
+        .max_depth(2)
+        .include(|link| is_fixture(&link.key))
+        .crawl(&Request::browse())
+        .unwrap();
+    assert!(crawl.failures.is_empty());
+    assert!(crawl.truncated.is_empty());
+
+    let document = crawl.document;
+    assert_eq!(document.head.title, "Browse");
+    let local = group(&document.outlines[0]);
+    assert_eq!(local.text, "Local Radio");
+    assert_eq!(local.key, "local");
+    assert_eq!(local.attributes.get("URL"), Some(LOCAL));
+    assert_eq!(local.outlines[0].text(), "Radio Kraków");
+
+    let music = group(&document.outlines[1]);
+    let genres = group(&music.outlines[0]);
+    let rock = group(&genres.outlines[0]);
+    assert_eq!(rock.attributes.get("guide_id"), Some("g19"));
+    assert_eq!(rock.attributes.get("item"), Some("genre"));
+    assert_eq!(group(&rock.outlines[0]).outlines.len(), 5);
+    // Already crawled from the root, and not followed again.
+    assert!(matches!(&genres.outlines[1], Outline::Link(link) if link.key == "local"));
+    assert!(matches!(&music.outlines[1], Outline::Link(link) if link.url == "/Browse.ashx"));
+    // Beyond the maximum depth.
+    assert!(matches!(
+        &group(&rock.outlines[1]).outlines[0],
+        Outline::Link(_)
+    ));
+
+    assert!(matches!(&document.outlines[2], Outline::Link(_)));
+    assert_eq!(
+        client.transport().requests(),
+        vec![
+            ROOT,
+            LOCAL,
+            MUSIC,
+            "http://opml.radiotime.com/Browse.ashx?id=g19",
+            "http://opml.radiotime.com/Browse.ashx?id=g19&offset=2",
+            "http://opml.radiotime.com/Browse.ashx?id=g19&filter=p&offset=1",
+            "http://opml.radiotime.com/Browse.ashx?id=g19&offset=4",
+        ]
+    );
+}
+
+#[test]
+fn concurrency() {
+    let sequential = Client::new(transport());
+    let expected = Crawler::new(&sequential)
+        .include(|link| is_fixture(&link.key))
+        .exclude(|link| link.text == "Rock Hour")
+        .crawl(&Request::browse())
+        .unwrap();
+
+    let concurrent = Client::new(transport());
+    let crawl = Crawler::new(&concurrent)
+        .concurrency(4)
+        .include(|link| is_fixture(&link.key))
+        .exclude(|link| link.text == "Rock Hour")
+        .crawl(&Request::browse())
+        .unwrap();
+
+    assert_eq!(crawl.document, expected.document);
+    let mut requests = concurrent.transport().requests();
+    requests.sort();
+    let mut expected_requests = sequential.transport().requests();
+    expected_requests.sort();
+    assert_eq!(requests, expected_requests);
+}
+
+#[test]
+fn failures() {
+    let client = Client::new(transport().file(LANG, "tests/documents/fault_1.opml"));
+    let mut fetched = vec![];
+    let mut failed = vec![];
+    let crawl = Crawler::new(&client)
+        .max_depth(1)
+        .concurrency(3)
+        .on_progress(|progress| match progress {
+            Progress::Fetched { url, depth, .. } => fetched.push((url.to_string(), depth)),
+            Progress::Failed { url, .. } => failed.push(url.to_string()),
+            _ => {}
+        })
+        .crawl(&Request::browse())
+        .unwrap();
+
+    assert_eq!(crawl.failures.len(), 5);
+    for failure in &crawl.failures {
+        match failure.error.kind() {
+            ErrorKind::Fault(fault) => {
+                assert_eq!(failure.url.as_str(), LANG);
+                assert_eq!(fault.kind(), FaultKind::InvalidId);
+            }
+            kind => assert!(matches!(kind, ErrorKind::Io(_)), "{:?}", kind),
+        }
+    }
+    assert_eq!(
+        crawl
+            .failures
+            .iter()
+            .filter(|failure| matches!(failure.error.kind(), ErrorKind::Io(_)))
+            .count(),
+        4
+    );
+    assert_eq!(crawl.document.links().count(), 5 + 3);
+
+    fetched.sort();
+    assert_eq!(
+        fetched,
+        vec![
+            (ROOT.to_string(), 0),
+            (LOCAL.to_string(), 1),
+            (MUSIC.to_string(), 1)
+        ]
+    );
+    failed.sort();
+    assert_eq!(failed[0], LANG);
+    assert_eq!(failed.len(), 5);
+}
+
+#[test]
+fn max_depth_zero() {
+    let client = Client::new(transport());
+    let crawl = Crawler::new(&client)
+        .max_depth(0)
+        .crawl(&Request::browse())
+        .unwrap();
+    assert_eq!(crawl.document.links().count(), 7);
+    assert_eq!(client.transport().requests(), vec![ROOT]);
+}
+
+#[test]
+fn root_failure() {
+    let client = Client::new(FixtureTransport::new());
+    let error = Crawler::new(&client).crawl(&Request::browse()).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Io(_)));
+}
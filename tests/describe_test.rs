@@ -0,0 +1,150 @@
+mod support;
+
+use std::fs::File;
+use support::FixtureTransport;
+use tunein::client::Client;
+use tunein::{Detail, Event, Object, Outline, OutlineEvent, Reader, ReaderConfig};
+
+fn read_object(path: &str) -> Object {
+    let mut document = tunein::read(File::open(path).unwrap()).unwrap();
+    assert_eq!(document.outlines.len(), 1);
+    match document.outlines.remove(0) {
+        Outline::Object(object) => object,
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+}
+
+#[test]
+fn station() {
+    let object = read_object("tests/documents/describe_1.opml");
+    assert_eq!(object.text, "KRK.FM 102.4");
+    let station = match object.detail {
+        Some(Detail::Station(station)) => station,
+        detail => panic!("Unexpected detail {:?}", detail),
+    };
+    assert_eq!(station.guide_id, "s16527".parse().ok());
+    assert_eq!(station.preset_id, "s16527".parse().ok());
+    assert_eq!(station.name, "KRK.FM");
+    assert_eq!(station.call_sign, "KRK.FM 102.4");
+    assert_eq!(station.slogan, "Polska");
+    assert_eq!(station.description, "Radio z Krakowa & okolic.");
+    assert_eq!(station.frequency, "102.4");
+    assert_eq!(station.band, "FM");
+    assert_eq!(station.url, "http://www.krkfm.pl");
+    assert_eq!(
+        station.logo,
+        "http://cdn-radiotime-logos.tunein.com/s16527q.png"
+    );
+    assert_eq!(station.location, "Kraków, Poland");
+    assert_eq!(station.genre_id, "g61".parse().ok());
+    assert_eq!(station.genre_name, "Top 40-Pop");
+    assert_eq!(station.region_id, "r101306".parse().ok());
+    assert_eq!(station.language, "Polish");
+    assert_eq!(station.twitter_id, "krkfm");
+    assert!(station.is_available);
+    assert!(station.is_music);
+    assert!(station.has_song);
+    assert!(!station.has_schedule);
+    assert!(!station.has_topics);
+    assert_eq!(station.field("current_artist"), Some("The Temper Trap"));
+    assert_eq!(station.field("report_url"), Some(""));
+    assert_eq!(station.field("tz_offset"), Some("60"));
+}
+
+#[test]
+fn program() {
+    let object = read_object("tests/documents/describe_2.opml");
+    let program = match object.detail {
+        Some(Detail::Program(program)) => program,
+        detail => panic!("Unexpected detail {:?}", detail),
+    };
+    assert_eq!(program.guide_id, "p48921".parse().ok());
+    assert_eq!(program.name, "Lista Przebojów Trójki");
+    assert_eq!(program.hosts, "Marek Niedźwiecki");
+    assert_eq!(program.genre_id, "g61".parse().ok());
+    assert_eq!(program.twitter_id, "");
+    assert!(program.is_available);
+    assert!(program.has_topics);
+    assert_eq!(program.field("is_event"), Some("false"));
+}
+
+#[test]
+fn topic() {
+    let object = read_object("tests/documents/describe_3.opml");
+    match object.detail {
+        Some(Detail::Other { name, fields }) => {
+            assert_eq!(name, "topic");
+            assert_eq!(fields.get("title"), Some("Notowanie 1987"));
+            assert_eq!(fields.get("program_id"), Some("p48921"));
+            assert_eq!(fields.len(), 4);
+        }
+        detail => panic!("Unexpected detail {:?}", detail),
+    }
+}
+
+#[test]
+fn events() {
+    let source = r#"<opml version="1"><body>
+        <outline type="object" text="Empty"></outline>
+        <outline type="text" text="After"/>
+    </body></opml>"#;
+    let events: Vec<_> = Reader::new(source.as_bytes())
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        events[2..6],
+        [
+            Event::StartOutline(Box::new(OutlineEvent::Object(Object {
+                text: "Empty".to_string(),
+                ..Default::default()
+            }))),
+            Event::EndOutline,
+            Event::StartOutline(Box::new(OutlineEvent::Text("After".into()))),
+            Event::EndOutline,
+        ]
+    );
+}
+
+#[test]
+fn nested_field_elements() {
+    let source = r#"<opml version="1"><body><outline type="object" text="Station">
+        <station><name>Radio <b>One</b></name></station>
+        <station><name>Duplicate</name></station>
+    </outline></body></opml>"#;
+
+    assert!(tunein::read(source.as_bytes()).is_err());
+
+    let parsed = tunein::read_with_config(source.as_bytes(), ReaderConfig::lenient()).unwrap();
+    match &parsed.document.outlines[0] {
+        Outline::Object(Object {
+            detail: Some(Detail::Station(station)),
+            ..
+        }) => assert_eq!(station.name, "Radio "),
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+    let warnings: Vec<_> = parsed.warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "Unexpected element <b> at 2:30",
+            "Unexpected element <station> at 3:9",
+        ]
+    );
+}
+
+#[test]
+fn client_describe() {
+    let client = Client::new(FixtureTransport::new().file(
+        "http://opml.radiotime.com/Describe.ashx?id=p48921",
+        "tests/documents/describe_2.opml",
+    ));
+    let document = client.describe(&"p48921".parse().unwrap()).unwrap();
+    assert!(matches!(
+        &document.outlines[0],
+        Outline::Object(Object {
+            detail: Some(Detail::Program(_)),
+            ..
+        })
+    ));
+}
@@ -0,0 +1,68 @@
+use tunein::{ErrorKind, Position};
+
+#[test]
+fn unexpected_element() {
+    let input = "<opml version=\"1\">\n  <head>\n    <expires>3600</expires>".as_bytes();
+    let error = tunein::read(input).unwrap_err();
+
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedElement { name } if name == "expires"));
+    assert_eq!(error.position(), Some(Position { line: 3, column: 5 }));
+    assert_eq!(error.element(), Some("expires"));
+    assert_eq!(error.to_string(), "Unexpected element <expires> at 3:5");
+}
+
+#[test]
+fn invalid_bitrate() {
+    let input = "<opml version=\"1\"><body>\n<outline type=\"audio\" bitrate=\"fast\"/>\
+                 </body></opml>"
+        .as_bytes();
+    let error = tunein::read(input).unwrap_err();
+
+    assert!(matches!(
+        error.kind(),
+        ErrorKind::InvalidBitrateFormat { .. }
+    ));
+    assert_eq!(error.position(), Some(Position { line: 2, column: 1 }));
+    assert_eq!(error.element(), Some("outline"));
+    assert_eq!(error.attribute(), Some("bitrate"));
+    assert_eq!(error.value(), Some("fast"));
+    assert_eq!(error.to_string(), "Invalid bitrate format \"fast\" at 2:1");
+}
+
+#[test]
+fn invalid_outline_type() {
+    let input = "<opml version=\"1\"><body><outline type=\"video\"/></body></opml>".as_bytes();
+    let error = tunein::read(input).unwrap_err();
+
+    assert!(matches!(error.kind(), ErrorKind::InvalidOutlineType { .. }));
+    assert_eq!(error.attribute(), Some("type"));
+    assert_eq!(error.value(), Some("video"));
+    assert_eq!(
+        error.position(),
+        Some(Position {
+            line: 1,
+            column: 25
+        })
+    );
+}
+
+#[test]
+fn invalid_version() {
+    let error = tunein::read("<opml version=\"one\"></opml>".as_bytes()).unwrap_err();
+
+    assert!(matches!(
+        error.kind(),
+        ErrorKind::InvalidVersionFormat { .. }
+    ));
+    assert_eq!(error.value(), Some("one"));
+    assert_eq!(error.position(), Some(Position { line: 1, column: 1 }));
+}
+
+#[test]
+fn xml_error() {
+    let input = "<opml version=\"1\">\n<head>".as_bytes();
+    let error = tunein::read(input).unwrap_err();
+
+    assert!(matches!(error.kind(), ErrorKind::XmlError(_)));
+    assert!(error.position().is_some());
+}
@@ -0,0 +1,140 @@
+mod support;
+
+use std::fs::File;
+use support::FixtureTransport;
+use tunein::client::Client;
+use tunein::{ErrorKind, Fault, FaultKind};
+
+fn read_fault(path: &str) -> Fault {
+    let document = tunein::read(File::open(path).unwrap()).unwrap();
+    match document.into_result().unwrap_err().kind() {
+        ErrorKind::Fault(fault) => fault.clone(),
+        kind => panic!("Unexpected error {:?}", kind),
+    }
+}
+
+#[test]
+fn head() {
+    let document = tunein::read(File::open("tests/documents/fault_1.opml").unwrap()).unwrap();
+    assert_eq!(document.head.status, Some(400));
+    assert_eq!(document.head.fault.as_deref(), Some("Invalid id"));
+    assert_eq!(
+        document.head.fault_code.as_deref(),
+        Some("validation.invalidId")
+    );
+}
+
+#[test]
+fn invalid_id() {
+    let fault = read_fault("tests/documents/fault_1.opml");
+    assert_eq!(
+        fault,
+        Fault {
+            status: 400,
+            message: "Invalid id".to_string(),
+            code: Some("validation.invalidId".to_string()),
+        }
+    );
+    assert_eq!(fault.kind(), FaultKind::InvalidId);
+    assert_eq!(
+        fault.to_string(),
+        "Fault 400 (validation.invalidId): Invalid id"
+    );
+}
+
+#[test]
+fn rate_limited() {
+    let fault = read_fault("tests/documents/fault_2.opml");
+    assert_eq!(fault.kind(), FaultKind::RateLimited);
+}
+
+#[test]
+fn server_error() {
+    let fault = read_fault("tests/documents/fault_3.opml");
+    assert_eq!(fault.kind(), FaultKind::ServerError);
+    assert_eq!(fault.to_string(), "Fault 500: An unexpected error occurred");
+}
+
+#[test]
+fn invalid_request() {
+    let fault = Fault {
+        status: 400,
+        message: "Invalid root category".to_string(),
+        code: Some("validation.rootCategory".to_string()),
+    };
+    assert_eq!(fault.kind(), FaultKind::InvalidRequest);
+}
+
+#[test]
+fn success() {
+    for path in &[
+        "tests/documents/sample_1.opml",
+        "tests/documents/sample_2.opml",
+        "tests/documents/describe_1.opml",
+    ] {
+        let document = tunein::read(File::open(path).unwrap()).unwrap();
+        assert!(document.into_result().is_ok(), "{}", path);
+    }
+}
+
+#[test]
+fn round_trip() {
+    let document = tunein::read(File::open("tests/documents/fault_1.opml").unwrap()).unwrap();
+
+    let mut buffer = Vec::new();
+    tunein::write(&document, &mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+
+    assert!(output.contains("<fault>Invalid id</fault>"));
+    assert!(output.contains("<fault_code>validation.invalidId</fault_code>"));
+    assert_eq!(tunein::read(output.as_bytes()).unwrap(), document);
+}
+
+#[test]
+fn client() {
+    let client = Client::new(FixtureTransport::new().file(
+        "http://opml.radiotime.com/Describe.ashx?id=s0",
+        "tests/documents/fault_1.opml",
+    ));
+    let error = client
+        .describe(&"s0".parse().unwrap())
+        .and_then(|document| document.into_result())
+        .unwrap_err();
+    assert!(
+        matches!(error.kind(), ErrorKind::Fault(fault) if fault.kind() == FaultKind::InvalidId)
+    );
+}
+
+#[test]
+fn client_error_status() {
+    let body = |path| std::fs::read(path).unwrap();
+    let client = Client::new(
+        FixtureTransport::new()
+            .body(
+                "http://opml.radiotime.com/Describe.ashx?id=s0",
+                400,
+                body("tests/documents/fault_1.opml"),
+            )
+            .body(
+                "http://opml.radiotime.com/Describe.ashx?id=s1",
+                429,
+                body("tests/documents/fault_2.opml"),
+            )
+            .body(
+                "http://opml.radiotime.com/Describe.ashx?id=s2",
+                503,
+                "<html>Service Unavailable</html>",
+            ),
+    );
+    let kind = |id: &str| match client.describe(&id.parse().unwrap()).unwrap_err().kind() {
+        ErrorKind::Fault(fault) => Some(fault.kind()),
+        ErrorKind::HttpStatus { status } => {
+            assert_eq!(*status, 503);
+            None
+        }
+        kind => panic!("Unexpected error {:?}", kind),
+    };
+    assert_eq!(kind("s0"), Some(FaultKind::InvalidId));
+    assert_eq!(kind("s1"), Some(FaultKind::RateLimited));
+    assert_eq!(kind("s2"), None);
+}
@@ -0,0 +1,52 @@
+use tunein::{Format, Outline};
+
+#[test]
+fn parse_list() {
+    assert_eq!(
+        Format::parse_list("aac,mp3"),
+        vec![Format::AAC, Format::MP3]
+    );
+    assert_eq!(
+        Format::parse_list("ogg, wma,hls,flash,real,flac,aacp"),
+        vec![
+            Format::OGG,
+            Format::WMA,
+            Format::HLS,
+            Format::Flash,
+            Format::Real,
+            Format::FLAC,
+            Format::AACPlus,
+        ]
+    );
+    assert_eq!(
+        Format::parse_list("MP3,wmpro,mp3"),
+        vec![Format::MP3, Format::Other("wmpro".into())]
+    );
+    assert!(Format::parse_list("").is_empty());
+}
+
+#[test]
+fn join_list() {
+    let formats = vec![Format::AACPlus, Format::MP3, Format::Other("qt".into())];
+    assert_eq!(Format::join_list(&formats), "aacp,mp3,qt");
+    assert_eq!(Format::parse_list(&Format::join_list(&formats)), formats);
+}
+
+#[test]
+fn audio_formats() {
+    let input = r#"<opml version="1"><body>
+        <outline type="audio" text="Multi" formats="aac,mp3"/>
+        <outline type="audio" text="None"/>
+    </body></opml>"#;
+    let document = tunein::read(input.as_bytes()).unwrap();
+
+    let formats: Vec<_> = document
+        .outlines
+        .iter()
+        .map(|outline| match outline {
+            Outline::Audio(audio) => audio.formats.clone(),
+            outline => panic!("Unexpected outline {:?}", outline),
+        })
+        .collect();
+    assert_eq!(formats, vec![vec![Format::AAC, Format::MP3], vec![]]);
+}
@@ -0,0 +1,99 @@
+use std::fs::File;
+use tunein::{ErrorKind, GuideId, GuideIdKind, Outline, ReaderConfig};
+
+#[test]
+fn parse() {
+    let id: GuideId = "s76368".parse().unwrap();
+    assert_eq!(id.kind(), GuideIdKind::Station);
+    assert_eq!(id.number(), 76368);
+    assert_eq!(id.to_string(), "s76368");
+
+    let kinds: Vec<_> = ["p1", "t2", "r100526", "g19", "c3", "l4", "a5", "m6", "u7"]
+        .iter()
+        .map(|id| id.parse::<GuideId>().unwrap().kind())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            GuideIdKind::Program,
+            GuideIdKind::Topic,
+            GuideIdKind::Region,
+            GuideIdKind::Genre,
+            GuideIdKind::Category,
+            GuideIdKind::Language,
+            GuideIdKind::Artist,
+            GuideIdKind::Show,
+            GuideIdKind::Other('u'),
+        ]
+    );
+}
+
+#[test]
+fn invalid() {
+    for id in &[
+        "",
+        "s",
+        "76368",
+        "S76368",
+        "s76a",
+        "s+1",
+        "ss1",
+        "s99999999999999999999",
+    ] {
+        assert!(id.parse::<GuideId>().is_err(), "{} should be invalid", id);
+    }
+}
+
+#[test]
+fn new() {
+    let id = GuideId::new(GuideIdKind::Genre, 19).unwrap();
+    assert_eq!(id.as_str(), "g19");
+    assert_eq!(id, "g19".parse().unwrap());
+
+    let id = GuideId::new(GuideIdKind::Other('u'), 7).unwrap();
+    assert_eq!(id.kind(), GuideIdKind::Other('u'));
+    assert_eq!(id.number(), 7);
+
+    for prefix in &['é', 'S', '1', 's'] {
+        assert!(
+            GuideId::new(GuideIdKind::Other(*prefix), 5).is_err(),
+            "{:?} should be invalid",
+            prefix
+        );
+    }
+}
+
+#[test]
+fn sample_3_ids() {
+    let document = tunein::read(File::open("tests/documents/sample_3.opml").unwrap()).unwrap();
+    match &document.outlines[1] {
+        Outline::Link(link) => {
+            let id = link.guide_id.as_ref().unwrap();
+            assert_eq!(id.kind(), GuideIdKind::Region);
+            assert_eq!(id.number(), 100526);
+        }
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+}
+
+#[test]
+fn invalid_attribute() {
+    let input = r#"<opml version="1"><body>
+        <outline type="audio" text="Station" guide_id="s1" genre_id="rock"/>
+    </body></opml>"#;
+
+    let error = tunein::read(input.as_bytes()).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::InvalidGuideId { .. }));
+    assert_eq!(error.attribute(), Some("genre_id"));
+    assert_eq!(error.value(), Some("rock"));
+
+    let parsed = tunein::read_with_config(input.as_bytes(), ReaderConfig::lenient()).unwrap();
+    assert_eq!(parsed.warnings.len(), 1);
+    match &parsed.document.outlines[0] {
+        Outline::Audio(audio) => {
+            assert_eq!(audio.guide_id, Some("s1".parse().unwrap()));
+            assert_eq!(audio.genre_id, None);
+        }
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+}
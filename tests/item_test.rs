@@ -0,0 +1,68 @@
+use std::fs::File;
+use tunein::{Item, Outline};
+
+#[test]
+fn sample_2_items() {
+    let document = tunein::read(File::open("tests/documents/sample_2.opml").unwrap()).unwrap();
+    let stations = match &document.outlines[0] {
+        Outline::Group(group) => &group.outlines,
+        outline => panic!("Unexpected outline {:?}", outline),
+    };
+    assert_eq!(stations.len(), 4);
+    for outline in stations {
+        match outline {
+            Outline::Audio(audio) => assert_eq!(audio.item, Some(Item::Station)),
+            outline => panic!("Unexpected outline {:?}", outline),
+        }
+    }
+}
+
+#[test]
+fn parse() {
+    let items: Vec<Item> = [
+        "station", "topic", "show", "program", "song", "artist", "podcast",
+    ]
+    .iter()
+    .map(|item| item.parse().unwrap())
+    .collect();
+    assert_eq!(
+        items,
+        vec![
+            Item::Station,
+            Item::Topic,
+            Item::Show,
+            Item::Program,
+            Item::Song,
+            Item::Artist,
+            Item::Other("podcast".into()),
+        ]
+    );
+    for item in items {
+        assert_eq!(item.to_string().parse::<Item>().unwrap(), item);
+    }
+}
+
+#[test]
+fn link_and_audio_items() {
+    let input = r#"<opml version="1"><body>
+        <outline type="link" text="Morning Show" URL="http://opml.radiotime.com/Browse.ashx?c=pbrowse&amp;id=p1" item="show"/>
+        <outline type="audio" text="Episode 1" item="topic"/>
+        <outline type="link" text="Music"/>
+    </body></opml>"#;
+    let document = tunein::read(input.as_bytes()).unwrap();
+
+    let items: Vec<_> = document
+        .outlines
+        .iter()
+        .map(|outline| match outline {
+            Outline::Link(link) => link.item.clone(),
+            Outline::Audio(audio) => audio.item.clone(),
+            outline => panic!("Unexpected outline {:?}", outline),
+        })
+        .collect();
+    assert_eq!(items, vec![Some(Item::Show), Some(Item::Topic), None]);
+
+    let mut buffer = Vec::new();
+    tunein::write(&document, &mut buffer).unwrap();
+    assert_eq!(tunein::read(buffer.as_slice()).unwrap(), document);
+}
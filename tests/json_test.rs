@@ -0,0 +1,116 @@
+#![cfg(feature = "json")]
+
+use std::fs::File;
+use tunein::{ErrorKind, Outline, ReaderConfig};
+
+fn same_as_xml(name: &str) {
+    let xml = tunein::read(File::open(format!("tests/documents/{}.opml", name)).unwrap());
+    let json = tunein::read_json(File::open(format!("tests/documents/{}.json", name)).unwrap());
+    assert_eq!(json.unwrap(), xml.unwrap());
+}
+
+#[test]
+fn sample_1() {
+    same_as_xml("sample_1");
+}
+
+#[test]
+fn sample_2() {
+    same_as_xml("sample_2");
+}
+
+#[test]
+fn sample_3() {
+    same_as_xml("sample_3");
+}
+
+#[test]
+fn describe() {
+    same_as_xml("describe_1");
+}
+
+#[test]
+fn numbers_and_booleans() {
+    let input = r#"{
+        "head": { "title": "Numbers", "status": 200 },
+        "body": [{ "element": "outline", "type": "audio", "text": "Station", "bitrate": 128, "is_direct": true }]
+    }"#;
+    let document = tunein::read_json(input.as_bytes()).unwrap();
+    assert_eq!(document.head.status, Some(200));
+    match &document.outlines[0] {
+        Outline::Audio(audio) => {
+            assert_eq!(audio.bitrate, Some(128));
+            assert_eq!(audio.attribute("is_direct"), Some("true"));
+        }
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+}
+
+#[test]
+fn lenient() {
+    let input = r#"{
+        "head": { "title": "Lenient", "expires": "3600" },
+        "body": [
+            { "element": "promo", "text": "Hidden" },
+            { "element": "outline", "type": "audio", "text": "Station", "bitrate": "high" },
+            { "element": "outline", "type": "video", "text": "Clip" }
+        ]
+    }"#;
+
+    let error = tunein::read_json(input.as_bytes()).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedElement { name } if name == "expires"));
+
+    let parsed = tunein::read_json_with_config(input.as_bytes(), ReaderConfig::lenient()).unwrap();
+    assert_eq!(parsed.document.outlines.len(), 1);
+    let warnings: Vec<_> = parsed.warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "Unexpected element <expires>",
+            "Unexpected element <promo>",
+            "Invalid bitrate format \"high\"",
+            "Invalid outline type \"video\"",
+        ]
+    );
+}
+
+#[test]
+fn children_of_non_group_outlines() {
+    let xml = r#"<opml version="1"><body>
+        <outline type="link" text="Link" URL="http://example.com"><outline type="audio" text="Nested"/></outline>
+        <outline type="text" text="Last"/>
+    </body></opml>"#;
+    let json = r#"{ "body": [
+        { "element": "outline", "type": "link", "text": "Link", "URL": "http://example.com",
+          "children": [{ "element": "outline", "type": "audio", "text": "Nested" }] },
+        { "element": "outline", "type": "text", "text": "Last" }
+    ] }"#;
+
+    let error = tunein::read_json(json.as_bytes()).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedElement { name } if name == "outline"));
+
+    let from_xml = tunein::read_with_config(xml.as_bytes(), ReaderConfig::lenient()).unwrap();
+    let from_json =
+        tunein::read_json_with_config(json.as_bytes(), ReaderConfig::lenient()).unwrap();
+    assert_eq!(from_json.document.outlines, from_xml.document.outlines);
+    // Only the OPML reader knows positions.
+    let warnings = |parsed: &tunein::Parsed| -> Vec<String> {
+        parsed
+            .warnings
+            .iter()
+            .map(|w| format!("{:?}", w.kind()))
+            .collect()
+    };
+    assert_eq!(warnings(&from_json), warnings(&from_xml));
+}
+
+#[test]
+fn invalid_structure() {
+    let error =
+        tunein::read_json(r#"{ "body": { "text": "Not a list" } }"#.as_bytes()).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::JsonError(_)));
+    assert_eq!(error.to_string(), "outlines is not an array");
+
+    let error = tunein::read_json("<opml/>".as_bytes()).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::JsonError(_)));
+}
@@ -0,0 +1,95 @@
+use tunein::{ErrorKind, Outline, ReaderConfig};
+
+const INPUT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="1">
+    <head>
+        <title>Lenient</title>
+        <expires>3600</expires>
+        <status>200</status>
+    </head>
+    <body>
+        <promo><outline type="link" text="Hidden"/></promo>
+        <outline type="audio" text="Station" bitrate="high" reliability="99"/>
+        <outline type="video" text="Clip"><outline type="text" text="Nested"/></outline>
+        <outline type="text" text="Last"/>
+    </body>
+</opml>"#;
+
+#[test]
+fn strict_fails() {
+    let error = tunein::read(INPUT.as_bytes()).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedElement { name } if name == "expires"));
+}
+
+#[test]
+fn lenient_recovers() {
+    let parsed = tunein::read_with_config(INPUT.as_bytes(), ReaderConfig::lenient()).unwrap();
+
+    assert_eq!(parsed.document.head.title, "Lenient");
+    assert_eq!(parsed.document.head.status, Some(200));
+    assert_eq!(parsed.document.outlines.len(), 2);
+    match &parsed.document.outlines[0] {
+        Outline::Audio(audio) => {
+            assert_eq!(audio.text, "Station");
+            assert_eq!(audio.bitrate, None);
+            assert_eq!(audio.reliability, Some(99));
+        }
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+    assert_eq!(parsed.document.outlines[1], Outline::Text("Last".into()));
+
+    let warnings: Vec<_> = parsed.warnings.iter().map(|w| w.to_string()).collect();
+    assert_eq!(
+        warnings,
+        vec![
+            "Unexpected element <expires> at 5:9",
+            "Unexpected element <promo> at 9:9",
+            "Invalid bitrate format \"high\" at 10:9",
+            "Invalid outline type \"video\" at 11:9",
+        ]
+    );
+}
+
+#[test]
+fn strict_has_no_warnings() {
+    let parsed = tunein::read_with_config(
+        std::fs::File::open("tests/documents/sample_2.opml").unwrap(),
+        ReaderConfig::default(),
+    )
+    .unwrap();
+    assert!(parsed.warnings.is_empty());
+}
+
+#[test]
+fn lenient_non_opml_root() {
+    for input in &[
+        "<foo/>",
+        "<html><body><h1>502 Bad Gateway</h1></body></html>",
+    ] {
+        let error =
+            tunein::read_with_config(input.as_bytes(), ReaderConfig::lenient()).unwrap_err();
+        assert!(
+            matches!(error.kind(), ErrorKind::MissingOpmlElement),
+            "{}",
+            error
+        );
+        assert_eq!(error.element(), Some("opml"));
+    }
+}
+
+#[test]
+fn children_of_non_group_outlines() {
+    let input = r#"<opml version="1"><body>
+        <outline type="link" text="Link" URL="http://example.com"><outline type="audio" text="Nested"/></outline>
+        <outline type="text" text="Last"/>
+    </body></opml>"#;
+
+    let error = tunein::read(input.as_bytes()).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::UnexpectedElement { name } if name == "outline"));
+
+    let parsed = tunein::read_with_config(input.as_bytes(), ReaderConfig::lenient()).unwrap();
+    assert_eq!(parsed.document.outlines.len(), 2);
+    assert!(matches!(&parsed.document.outlines[0], Outline::Link(link) if link.text == "Link"));
+    assert_eq!(parsed.document.outlines[1], Outline::Text("Last".into()));
+    assert_eq!(parsed.warnings.len(), 1);
+}
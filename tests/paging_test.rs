@@ -0,0 +1,137 @@
+mod support;
+
+use support::FixtureTransport;
+use tunein::client::Client;
+use tunein::request::Request;
+use tunein::{ErrorKind, FaultKind, Outline};
+
+const PAGE_1: &str = "http://opml.radiotime.com/Browse.ashx?id=g19";
+const PAGE_2: &str = "http://opml.radiotime.com/Browse.ashx?id=g19&offset=2";
+const PAGE_3: &str = "http://opml.radiotime.com/Browse.ashx?id=g19&offset=4";
+const SHOWS_2: &str = "http://opml.radiotime.com/Browse.ashx?id=g19&filter=p&offset=1";
+
+fn transport() -> FixtureTransport {
+    FixtureTransport::new()
+        .file(PAGE_1, "tests/documents/paged_1.opml")
+        .file(PAGE_2, "tests/documents/paged_2.opml")
+        .file(PAGE_3, "tests/documents/paged_3.opml")
+        .file(SHOWS_2, "tests/documents/paged_4.opml")
+}
+
+fn request() -> Request {
+    Request::browse().id(&"g19".parse().unwrap())
+}
+
+fn texts(outline: &Outline) -> Vec<&str> {
+    match outline {
+        Outline::Group(group) => group.outlines.iter().map(Outline::text).collect(),
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+}
+
+#[test]
+fn pages() {
+    let client = Client::new(transport());
+    let pages: Vec<_> = client.pages(&request()).collect::<Result<_, _>>().unwrap();
+
+    assert_eq!(pages.len(), 4);
+    assert_eq!(texts(&pages[0].outlines[0]), vec!["Rock FM", "Rock AM"]);
+    assert_eq!(texts(&pages[0].outlines[1]), vec!["Rock Hour"]);
+    assert_eq!(
+        texts(&pages[1].outlines[0]),
+        vec!["Rock 24", "Classic Rock"]
+    );
+    assert_eq!(texts(&pages[2].outlines[0]), vec!["Rock Ballads"]);
+    assert_eq!(texts(&pages[3].outlines[0]), vec!["Hard Rock"]);
+    assert!(pages
+        .iter()
+        .all(|page| page.links().all(|link| !link.key.starts_with("next"))));
+
+    assert_eq!(
+        client.transport().requests(),
+        vec![PAGE_1, PAGE_2, SHOWS_2, PAGE_3]
+    );
+}
+
+#[test]
+fn merge() {
+    let client = Client::new(transport());
+    let document = client.pages(&request()).merge().unwrap();
+
+    assert_eq!(document.head.title, "Rock");
+    assert_eq!(document.outlines.len(), 2);
+    assert_eq!(
+        texts(&document.outlines[0]),
+        vec!["Rock FM", "Rock AM", "Rock 24", "Classic Rock", "Hard Rock"]
+    );
+    assert_eq!(
+        texts(&document.outlines[1]),
+        vec!["Rock Hour", "Rock Ballads"]
+    );
+}
+
+#[test]
+fn limit() {
+    let client = Client::new(transport());
+    let mut pages = client.pages(&request()).limit(2);
+    let document = pages.merge().unwrap();
+
+    assert_eq!(document.audios().count(), 4);
+    assert_eq!(client.transport().requests(), vec![PAGE_1, PAGE_2]);
+    assert!(pages.truncated());
+
+    let mut pages = client.pages(&request()).limit(4);
+    pages.merge().unwrap();
+    assert!(!pages.truncated());
+}
+
+#[test]
+fn single_page() {
+    let client = Client::new(FixtureTransport::new().file(
+        "http://opml.radiotime.com/Browse.ashx",
+        "tests/documents/sample_1.opml",
+    ));
+    let pages: Vec<_> = client.pages(&Request::browse()).collect();
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].as_ref().unwrap().outlines.len(), 7);
+}
+
+#[test]
+fn stops_after_error() {
+    let client = Client::new(
+        FixtureTransport::new()
+            .file(PAGE_1, "tests/documents/paged_1.opml")
+            .body(PAGE_2, 500, ""),
+    );
+    let pages: Vec<_> = client.fetch_pages(&PAGE_1.parse().unwrap()).collect();
+    assert_eq!(pages.len(), 2);
+    assert!(matches!(
+        pages[1].as_ref().unwrap_err().kind(),
+        ErrorKind::HttpStatus { status: 500 }
+    ));
+    assert!(client.pages(&request()).merge().is_err());
+}
+
+#[test]
+fn fault_page() {
+    let client = Client::new(
+        FixtureTransport::new()
+            .file(PAGE_1, "tests/documents/paged_1.opml")
+            .file(PAGE_2, "tests/documents/fault_3.opml"),
+    );
+    let pages: Vec<_> = client.fetch_pages(&PAGE_1.parse().unwrap()).collect();
+    assert_eq!(pages.len(), 2);
+    assert!(matches!(
+        pages[1].as_ref().unwrap_err().kind(),
+        ErrorKind::Fault(fault) if fault.kind() == FaultKind::ServerError
+    ));
+    assert!(client.pages(&request()).merge().is_err());
+}
+
+#[test]
+fn take_continuations() {
+    let mut document =
+        tunein::read(std::fs::File::open("tests/documents/paged_1.opml").unwrap()).unwrap();
+    assert_eq!(document.take_continuations(), vec![PAGE_2, SHOWS_2]);
+    assert!(document.take_continuations().is_empty());
+}
@@ -0,0 +1,122 @@
+mod support;
+
+use std::fs::File;
+use support::FixtureTransport;
+use tunein::client::Client;
+use tunein::playlist::{Playlist, PlaylistKind};
+use tunein::tune::parse_playlist;
+use tunein::{Document, Url};
+
+const KRK_FM: &str = "http://opml.radiotime.com/Tune.ashx?id=s16527";
+const ANTY_RADIO: &str = "http://opml.radiotime.com/Tune.ashx?id=s76368";
+const LOGO: &str = "http://cdn-radiotime-logos.tunein.com/s16527q.png";
+
+fn document() -> Document {
+    tunein::read(File::open("tests/documents/playlist_1.opml").unwrap()).unwrap()
+}
+
+fn written(playlist: &Playlist, kind: PlaylistKind) -> String {
+    let mut buffer = Vec::new();
+    playlist.write(kind, &mut buffer).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[test]
+fn m3u() {
+    let document = document();
+    let m3u = written(&Playlist::from_document(&document), PlaylistKind::M3U);
+    assert_eq!(
+        m3u,
+        format!(
+            "#EXTM3U\n\
+             #PLAYLIST:Favourites\n\
+             #EXTINF:-1 tvg-logo=\"{}\" bitrate=\"128\",KRK.FM 102.4 - Polska\n\
+             {}\n\
+             #EXTINF:-1,Anty Radio & \"Friends\"\n\
+             {}\n",
+            LOGO, KRK_FM, ANTY_RADIO
+        )
+    );
+
+    let streams = parse_playlist(&m3u);
+    assert_eq!(streams.len(), 2);
+    assert_eq!(streams[0].url.as_str(), KRK_FM);
+    assert_eq!(streams[0].title.as_deref(), Some("KRK.FM 102.4 - Polska"));
+}
+
+#[test]
+fn pls() {
+    let document = document();
+    let pls = written(&Playlist::from_document(&document), PlaylistKind::PLS);
+    assert_eq!(
+        pls,
+        format!(
+            "[playlist]\n\
+             File1={}\n\
+             Title1=KRK.FM 102.4 - Polska\n\
+             Length1=-1\n\
+             File2={}\n\
+             Title2=Anty Radio & \"Friends\"\n\
+             Length2=-1\n\
+             NumberOfEntries=2\n\
+             Version=2\n",
+            KRK_FM, ANTY_RADIO
+        )
+    );
+
+    let streams = parse_playlist(&pls);
+    assert_eq!(streams.len(), 2);
+    assert_eq!(streams[1].url.as_str(), ANTY_RADIO);
+}
+
+#[test]
+fn xspf() {
+    let document = document();
+    let xspf = written(&Playlist::from_document(&document), PlaylistKind::XSPF);
+    for expected in &[
+        "<playlist xmlns=\"http://xspf.org/ns/0/\" version=\"1\">",
+        "<title>Favourites</title>",
+        &format!("<location>{}</location>", KRK_FM.replace('&', "&amp;")),
+        "<title>KRK.FM 102.4</title>",
+        "<annotation>Polska</annotation>",
+        &format!("<image>{}</image>", LOGO),
+        "<meta rel=\"http://opml.radiotime.com/bitrate\">128</meta>",
+        "<title>Anty Radio &amp; \"Friends\"</title>",
+    ] {
+        assert!(xspf.contains(expected), "{} in {}", expected, xspf);
+    }
+    assert_eq!(xspf.matches("<track>").count(), 2);
+    assert!(!xspf.contains("Off air"));
+}
+
+#[test]
+fn audios_subset() {
+    let document = document();
+    let playlist = Playlist::new(
+        document
+            .audios()
+            .filter(|audio| audio.bitrate.map_or(false, |bitrate| bitrate >= 128)),
+    );
+    assert_eq!(playlist.entries().len(), 1);
+    let m3u = written(&playlist, PlaylistKind::M3U);
+    assert!(m3u.starts_with("#EXTM3U\n#EXTINF:"));
+    assert!(!m3u.contains("Anty Radio"));
+}
+
+#[test]
+fn resolved_streams() {
+    let client =
+        Client::new(FixtureTransport::new().file(ANTY_RADIO, "tests/documents/tune_1.m3u"));
+    let document = document();
+    let playlist = Playlist::from_document(&document).resolve_with(|audio| {
+        let streams = client.resolve_audio(audio).ok()?;
+        streams.into_iter().next().map(|stream| stream.url)
+    });
+    let stream = Url::parse("http://ant-waw-01.cdn.eurozet.pl:8602/stream.mp3").unwrap();
+    assert_eq!(playlist.entries()[0].stream, None);
+    assert_eq!(playlist.entries()[1].stream, Some(stream.clone()));
+
+    let pls = written(&playlist, PlaylistKind::PLS);
+    assert!(pls.contains(&format!("File1={}\n", KRK_FM)));
+    assert!(pls.contains(&format!("File2={}\n", stream)));
+}
@@ -0,0 +1,176 @@
+mod support;
+
+use std::io;
+use std::time::{Duration, Instant};
+use support::ScriptedTransport;
+use tunein::client::Client;
+use tunein::policy::Policy;
+use tunein::{ErrorKind, Url};
+
+const BROWSE: &str = "tests/documents/sample_1.opml";
+
+fn policy() -> Policy {
+    Policy::new()
+        .retries(2)
+        .backoff(Duration::from_millis(1), Duration::from_millis(5))
+}
+
+#[test]
+fn retry_transport_errors() {
+    let transport = ScriptedTransport::new()
+        .fail(io::ErrorKind::ConnectionReset)
+        .fail(io::ErrorKind::TimedOut)
+        .file(BROWSE);
+    let client = Client::new(transport).with_policy(policy());
+    let document = client.browse().unwrap();
+    assert_eq!(document.head.title, "Browse");
+    assert_eq!(client.transport().requests().len(), 3);
+}
+
+#[test]
+fn give_up() {
+    let transport = ScriptedTransport::new()
+        .fail(io::ErrorKind::ConnectionReset)
+        .fail(io::ErrorKind::ConnectionReset)
+        .fail(io::ErrorKind::ConnectionReset)
+        .file(BROWSE);
+    let client = Client::new(transport).with_policy(policy());
+    let error = client.browse().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Io(_)));
+    assert_eq!(client.transport().requests().len(), 3);
+}
+
+#[test]
+fn no_retries_by_default() {
+    let transport = ScriptedTransport::new()
+        .fail(io::ErrorKind::ConnectionReset)
+        .file(BROWSE);
+    let client = Client::new(transport);
+    assert!(client.browse().is_err());
+    assert_eq!(client.transport().requests().len(), 1);
+}
+
+#[test]
+fn retry_server_errors() {
+    let transport = ScriptedTransport::new()
+        .respond(503, &[], "")
+        .file("tests/documents/fault_3.opml")
+        .file(BROWSE);
+    let client = Client::new(transport).with_policy(policy());
+    let document = client.browse().unwrap();
+    assert_eq!(document.head.title, "Browse");
+    assert_eq!(client.transport().requests().len(), 3);
+}
+
+#[test]
+fn client_errors_are_final() {
+    let transport = ScriptedTransport::new()
+        .file("tests/documents/fault_1.opml")
+        .respond(404, &[], "")
+        .file(BROWSE);
+    let client = Client::new(transport).with_policy(policy());
+
+    let document = client.browse().unwrap();
+    assert_eq!(document.head.status, Some(400));
+    let error = client.browse().unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        ErrorKind::HttpStatus { status: 404 }
+    ));
+    assert_eq!(client.transport().requests().len(), 2);
+}
+
+#[test]
+fn retry_resolve() {
+    let transport = ScriptedTransport::new()
+        .respond(500, &[], "")
+        .file("tests/documents/tune_1.m3u");
+    let client = Client::new(transport).with_policy(policy());
+    let url = Url::parse("http://opml.radiotime.com/Tune.ashx?id=s6").unwrap();
+    assert!(!client.resolve(&url).unwrap().is_empty());
+}
+
+#[test]
+fn backoff_delay() {
+    let policy = Policy::new().backoff(Duration::from_millis(100), Duration::from_secs(1));
+    let delays: Vec<_> = (0..6).map(|retry| policy.backoff_delay(retry)).collect();
+    assert_eq!(
+        delays,
+        vec![
+            Duration::from_millis(100),
+            Duration::from_millis(200),
+            Duration::from_millis(400),
+            Duration::from_millis(800),
+            Duration::from_secs(1),
+            Duration::from_secs(1),
+        ]
+    );
+    assert_eq!(policy.backoff_delay(100), Duration::from_secs(1));
+}
+
+#[test]
+fn rate_limit() {
+    let transport = ScriptedTransport::new()
+        .file(BROWSE)
+        .file(BROWSE)
+        .file(BROWSE)
+        .file(BROWSE);
+    let client = Client::new(transport).with_policy(Policy::new().rate_limit(50.0, 2));
+
+    let start = Instant::now();
+    for _ in 0..4 {
+        client.browse().unwrap();
+    }
+    // Two requests within the burst, then one every 20 ms.
+    assert!(start.elapsed() >= Duration::from_millis(35));
+}
+
+#[test]
+fn timeout_stops_retries() {
+    let transport = ScriptedTransport::new()
+        .fail(io::ErrorKind::ConnectionReset)
+        .file(BROWSE);
+    let policy = Policy::new()
+        .retries(3)
+        .backoff(Duration::from_millis(500), Duration::from_secs(1))
+        .timeout(Duration::from_millis(50));
+    let client = Client::new(transport).with_policy(policy);
+
+    let error = client.browse().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Io(_)));
+    assert_eq!(client.transport().requests().len(), 1);
+}
+
+#[test]
+fn timeout_while_rate_limited() {
+    let transport = ScriptedTransport::new().file(BROWSE).file(BROWSE);
+    let policy = Policy::new()
+        .rate_limit(1.0, 1)
+        .timeout(Duration::from_millis(50));
+    let client = Client::new(transport).with_policy(policy);
+
+    client.browse().unwrap();
+    let error = client.browse().unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::Timeout));
+    assert_eq!(client.transport().requests().len(), 1);
+}
+
+#[test]
+fn invalid_rate_limits() {
+    for per_second in &[0.0, -1.0, f64::NAN, f64::NEG_INFINITY] {
+        let transport = ScriptedTransport::new().file(BROWSE).file(BROWSE);
+        let policy = Policy::new()
+            .rate_limit(*per_second, 1)
+            .timeout(Duration::from_millis(50));
+        let client = Client::new(transport).with_policy(policy);
+
+        client.browse().unwrap();
+        let error = client.browse().unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Timeout), "{}", per_second);
+    }
+
+    let transport = ScriptedTransport::new().file(BROWSE).file(BROWSE);
+    let client = Client::new(transport).with_policy(Policy::new().rate_limit(f64::INFINITY, 1));
+    client.browse().unwrap();
+    client.browse().unwrap();
+}
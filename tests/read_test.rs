@@ -2,12 +2,12 @@ use std::fs::File;
 use std::io;
 
 #[cfg(test)]
-
 fn make_group(text: &str, key: &str, outlines: Vec<tunein::Outline>) -> tunein::Outline {
     tunein::Outline::Group(tunein::Group {
         text: text.into(),
         key: key.into(),
-        outlines: outlines,
+        outlines,
+        ..Default::default()
     })
 }
 
@@ -16,10 +16,12 @@ fn make_link(text: &str, url: &str, key: &str, guide_id: &str) -> tunein::Outlin
         text: text.into(),
         url: url.into(),
         key: key.into(),
-        guide_id: guide_id.into(),
+        guide_id: guide_id.parse().ok(),
+        ..Default::default()
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 fn make_audio(
     text: &str,
     subtext: &str,
@@ -38,15 +40,16 @@ fn make_audio(
         text: text.into(),
         subtext: subtext.into(),
         url: url.into(),
-        bitrate: bitrate,
-        reliability: reliability,
-        format: format,
-        item: item.into(),
+        bitrate: Some(bitrate),
+        reliability: Some(reliability),
+        formats: vec![format],
+        item: item.parse().ok(),
         image: image.into(),
-        guide_id: guide_id.into(),
-        genre_id: genre_id.into(),
-        now_playing_id: now_playing_id.into(),
-        preset_id: preset_id.into(),
+        guide_id: guide_id.parse().ok(),
+        genre_id: genre_id.parse().ok(),
+        now_playing_id: now_playing_id.parse().ok(),
+        preset_id: preset_id.parse().ok(),
+        ..Default::default()
     })
 }
 
@@ -63,6 +66,7 @@ fn sample_1() {
         head: tunein::Head {
             title: "Browse".into(),
             status: Some(200),
+            ..Default::default()
         },
         outlines: vec![
             make_link(
@@ -120,6 +124,7 @@ fn sample_2() {
         head: tunein::Head {
             title: "Kraków".into(),
             status: Some(300),
+            ..Default::default()
         },
         outlines: vec![make_group(
             "Stacje",
@@ -203,6 +208,7 @@ fn sample_3() {
         head: tunein::Head {
             title: "Angola".into(),
             status: Some(200),
+            ..Default::default()
         },
         outlines: vec![
             make_group(
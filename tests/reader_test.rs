@@ -0,0 +1,78 @@
+use std::fs::File;
+use tunein::{Event, OutlineEvent, Reader, Version};
+
+#[test]
+fn sample_1_events() {
+    let events: Vec<Event> = Reader::new(File::open("tests/documents/sample_1.opml").unwrap())
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(events.len(), 2 + 2 + 2 + 2 + 7 * 2);
+    assert_eq!(
+        events[0],
+        Event::StartDocument {
+            version: Version { major: 1, minor: 0 }
+        }
+    );
+    assert_eq!(events[1], Event::StartHead);
+    assert_eq!(events[2], Event::Title("Browse".into()));
+    assert_eq!(events[3], Event::Status(Some(200)));
+    assert_eq!(events[4], Event::EndHead);
+    assert_eq!(events[5], Event::StartBody);
+    match &events[6] {
+        Event::StartOutline(outline) => match outline.as_ref() {
+            OutlineEvent::Link(link) => assert_eq!(link.key, "local"),
+            outline => panic!("Unexpected outline {:?}", outline),
+        },
+        event => panic!("Unexpected event {:?}", event),
+    }
+    assert_eq!(events[7], Event::EndOutline);
+    assert_eq!(events[events.len() - 2], Event::EndBody);
+    assert_eq!(events[events.len() - 1], Event::EndDocument);
+}
+
+#[test]
+fn stop_early() {
+    let mut reader = Reader::new(File::open("tests/documents/sample_2.opml").unwrap());
+    let audio = loop {
+        if let Event::StartOutline(outline) = reader.next().unwrap() {
+            if let OutlineEvent::Audio(audio) = *outline {
+                break audio;
+            }
+        }
+    };
+    assert_eq!(audio.text, "Anty Radio 101.3 (Rock)");
+}
+
+#[test]
+fn minor_version() {
+    let input = "<opml version=\"1.1\"></opml>".as_bytes();
+    let mut events = Reader::new(input).into_iter();
+    assert_eq!(
+        events.next().unwrap().unwrap(),
+        Event::StartDocument {
+            version: Version { major: 1, minor: 1 }
+        }
+    );
+    assert_eq!(events.next().unwrap().unwrap(), Event::EndDocument);
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn events_stop_after_error() {
+    let input = "<opml version=\"1\"><unknown/></opml>".as_bytes();
+    let mut events = Reader::new(input).into_iter();
+    assert!(events.next().unwrap().is_ok());
+    assert!(events.next().unwrap().is_err());
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn next_after_end() {
+    let mut reader = Reader::new("<opml version=\"1\"></opml>".as_bytes());
+    assert!(matches!(reader.next(), Ok(Event::StartDocument { .. })));
+    for _ in 0..3 {
+        assert_eq!(reader.next().unwrap(), Event::EndDocument);
+    }
+}
@@ -0,0 +1,112 @@
+use tunein::request::{Endpoint, PresetAction, Render, Request, BROWSE_URI};
+use tunein::{ErrorKind, Format, GuideId, Item, Url};
+
+fn id(id: &str) -> GuideId {
+    id.parse().unwrap()
+}
+
+#[test]
+fn browse() {
+    assert_eq!(Request::browse().url().as_str(), BROWSE_URI);
+    assert_eq!(
+        Request::browse().category("music").url().as_str(),
+        "http://opml.radiotime.com/Browse.ashx?c=music"
+    );
+    assert_eq!(
+        Request::browse()
+            .id(&id("r100526"))
+            .filter("s:popular")
+            .offset(26)
+            .url()
+            .as_str(),
+        "http://opml.radiotime.com/Browse.ashx?id=r100526&filter=s%3Apopular&offset=26"
+    );
+}
+
+#[test]
+fn search() {
+    let request = Request::search("rock & roll")
+        .types(&[Item::Station, Item::Program])
+        .filter("s:popular");
+    assert_eq!(request.endpoint(), Endpoint::Search);
+    assert_eq!(
+        request.url().as_str(),
+        "http://opml.radiotime.com/Search.ashx?query=rock+%26+roll\
+         &types=station%2Cprogram&filter=s%3Apopular"
+    );
+}
+
+#[test]
+fn other_endpoints() {
+    assert_eq!(
+        Request::tune(&id("s76368")).url().as_str(),
+        "http://opml.radiotime.com/Tune.ashx?id=s76368"
+    );
+    assert_eq!(
+        Request::describe(&id("p1")).url().as_str(),
+        "http://opml.radiotime.com/Describe.ashx?id=p1"
+    );
+    assert_eq!(
+        Request::options(&id("s1")).url().as_str(),
+        "http://opml.radiotime.com/Options.ashx?id=s1"
+    );
+    assert_eq!(
+        Request::preset(PresetAction::Remove, &id("s1"))
+            .url()
+            .as_str(),
+        "http://opml.radiotime.com/Preset.ashx?c=remove&id=s1"
+    );
+}
+
+#[test]
+fn common_params() {
+    let url = Request::browse()
+        .category("local")
+        .partner_id("abc")
+        .serial("123")
+        .formats(&[Format::AAC, Format::MP3])
+        .locale("de")
+        .username("jan")
+        .render(Render::Json)
+        .latlon(50.06, 19.94)
+        .url();
+    let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    let expected: Vec<(String, String)> = vec![
+        ("c", "local"),
+        ("partnerId", "abc"),
+        ("serial", "123"),
+        ("formats", "aac,mp3"),
+        ("locale", "de"),
+        ("username", "jan"),
+        ("render", "json"),
+        ("latlon", "50.06,19.94"),
+    ]
+    .into_iter()
+    .map(|(n, v)| (n.to_string(), v.to_string()))
+    .collect();
+    assert_eq!(params, expected);
+}
+
+#[test]
+fn param_replaced() {
+    assert_eq!(
+        Request::browse().offset(1).offset(2).url().as_str(),
+        "http://opml.radiotime.com/Browse.ashx?offset=2"
+    );
+}
+
+#[test]
+fn base() {
+    let base = Url::parse("http://localhost:8080/tunein/").unwrap();
+    assert_eq!(
+        Request::search("a").base(base).unwrap().url().as_str(),
+        "http://localhost:8080/tunein/Search.ashx?query=a"
+    );
+
+    for base in &["mailto:tunein@example.com", "data:text/plain,tunein"] {
+        let error = Request::search("a")
+            .base(Url::parse(base).unwrap())
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidBaseUrl { value } if value == base));
+    }
+}
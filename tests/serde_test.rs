@@ -0,0 +1,109 @@
+#![cfg(feature = "serde")]
+
+use serde_json::json;
+use std::fs::File;
+use tunein::{Attributes, Document, Format, GuideId, Item, Outline, Text};
+
+fn read(path: &str) -> Document {
+    tunein::read(File::open(path).unwrap()).unwrap()
+}
+
+fn round_trip(path: &str) {
+    let document = read(path);
+    let json = serde_json::to_string(&document).unwrap();
+    let decoded: Document = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, document);
+}
+
+#[test]
+fn sample_1_round_trip() {
+    round_trip("tests/documents/sample_1.opml");
+}
+
+#[test]
+fn sample_2_round_trip() {
+    round_trip("tests/documents/sample_2.opml");
+}
+
+#[test]
+fn sample_3_round_trip() {
+    round_trip("tests/documents/sample_3.opml");
+}
+
+#[test]
+fn describe_round_trip() {
+    round_trip("tests/documents/describe_1.opml");
+    round_trip("tests/documents/describe_2.opml");
+    round_trip("tests/documents/describe_3.opml");
+}
+
+#[test]
+fn fault_round_trip() {
+    round_trip("tests/documents/fault_1.opml");
+}
+
+#[test]
+fn tagged_outlines() {
+    let document = read("tests/documents/sample_3.opml");
+    let value = serde_json::to_value(&document).unwrap();
+
+    assert_eq!(value["version"], json!({ "major": 1, "minor": 0 }));
+    assert_eq!(value["head"]["title"], "Angola");
+    assert_eq!(value["head"]["status"], 200);
+
+    let group = &value["outlines"][0];
+    assert_eq!(group["type"], "group");
+    assert_eq!(group["key"], "stations");
+
+    let audio = &group["outlines"][0];
+    assert_eq!(audio["type"], "audio");
+    assert_eq!(audio["bitrate"], 128);
+    assert_eq!(audio["formats"], json!(["mp3"]));
+    assert_eq!(audio["item"], "station");
+    assert_eq!(audio["guide_id"], "s6228");
+    assert_eq!(audio["genre_id"], "g158");
+}
+
+#[test]
+fn tagged_details() {
+    let value = serde_json::to_value(read("tests/documents/describe_3.opml")).unwrap();
+    let object = &value["outlines"][0];
+    assert_eq!(object["type"], "object");
+    assert_eq!(object["detail"]["type"], "other");
+    assert_eq!(object["detail"]["name"], "topic");
+    assert_eq!(object["detail"]["fields"]["duration"], "7200");
+}
+
+#[test]
+fn attributes_keep_order() {
+    let attributes: Attributes = vec![("z", "1"), ("a", "2"), ("m", "3")]
+        .into_iter()
+        .collect();
+    let json = serde_json::to_string(&attributes).unwrap();
+    assert_eq!(json, r#"{"z":"1","a":"2","m":"3"}"#);
+    let decoded: Attributes = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, attributes);
+}
+
+#[test]
+fn missing_fields_default() {
+    let outline: Outline = serde_json::from_value(json!({
+        "type": "text",
+        "text": "No stations"
+    }))
+    .unwrap();
+    assert_eq!(outline, Outline::Text(Text::from("No stations")));
+}
+
+#[test]
+fn strings() {
+    assert_eq!(
+        serde_json::to_value(vec![Format::AACPlus, Format::Other("opus".into())]).unwrap(),
+        json!(["aacp", "opus"])
+    );
+    assert_eq!(
+        serde_json::from_value::<Item>(json!("program")).unwrap(),
+        Item::Program
+    );
+    assert!(serde_json::from_value::<GuideId>(json!("station")).is_err());
+}
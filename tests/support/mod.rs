@@ -0,0 +1,126 @@
+#![allow(dead_code)]
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::sync::Mutex;
+use tunein::client::{Response, Transport};
+use tunein::Url;
+
+/// Transport serving canned responses by URL and recording requested URLs.
+#[derive(Default)]
+pub struct FixtureTransport {
+    routes: HashMap<String, (u16, Vec<u8>)>,
+    requests: Mutex<Vec<String>>,
+}
+
+impl FixtureTransport {
+    pub fn new() -> FixtureTransport {
+        FixtureTransport::default()
+    }
+
+    pub fn file(self, url: &str, path: &str) -> FixtureTransport {
+        self.body(url, 200, std::fs::read(path).unwrap())
+    }
+
+    pub fn body<B: Into<Vec<u8>>>(mut self, url: &str, status: u16, body: B) -> FixtureTransport {
+        self.routes.insert(url.to_string(), (status, body.into()));
+        self
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for FixtureTransport {
+    type Body = io::Cursor<Vec<u8>>;
+
+    fn get(&self, url: &Url) -> io::Result<Response<Self::Body>> {
+        self.requests.lock().unwrap().push(url.to_string());
+        match self.routes.get(url.as_str()) {
+            Some((status, body)) => Ok(Response {
+                status: *status,
+                headers: vec![],
+                body: io::Cursor::new(body.clone()),
+            }),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, url.to_string())),
+        }
+    }
+}
+
+/// Transport returning scripted responses in order, whatever the URL, and
+/// recording requested URLs with their request headers.
+#[derive(Default)]
+pub struct ScriptedTransport {
+    responses: Mutex<VecDeque<io::Result<Response<Vec<u8>>>>>,
+    requests: Mutex<Vec<ScriptedRequest>>,
+}
+
+/// Requested URL with the request headers.
+pub type ScriptedRequest = (String, Vec<(String, String)>);
+
+impl ScriptedTransport {
+    pub fn new() -> ScriptedTransport {
+        ScriptedTransport::default()
+    }
+
+    pub fn respond<B: Into<Vec<u8>>>(
+        self,
+        status: u16,
+        headers: &[(&str, &str)],
+        body: B,
+    ) -> ScriptedTransport {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        self.responses.lock().unwrap().push_back(Ok(Response {
+            status,
+            headers,
+            body: body.into(),
+        }));
+        self
+    }
+
+    pub fn file(self, path: &str) -> ScriptedTransport {
+        self.respond(200, &[], std::fs::read(path).unwrap())
+    }
+
+    pub fn fail(self, kind: io::ErrorKind) -> ScriptedTransport {
+        let error = io::Error::new(kind, "scripted failure");
+        self.responses.lock().unwrap().push_back(Err(error));
+        self
+    }
+
+    pub fn requests(&self) -> Vec<ScriptedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn urls(&self) -> Vec<String> {
+        self.requests().into_iter().map(|(url, _)| url).collect()
+    }
+}
+
+impl Transport for ScriptedTransport {
+    type Body = io::Cursor<Vec<u8>>;
+
+    fn get(&self, url: &Url) -> io::Result<Response<Self::Body>> {
+        self.get_with_headers(url, &[])
+    }
+
+    fn get_with_headers(
+        &self,
+        url: &Url,
+        headers: &[(String, String)],
+    ) -> io::Result<Response<Self::Body>> {
+        let request = (url.to_string(), headers.to_vec());
+        self.requests.lock().unwrap().push(request);
+        let response = self.responses.lock().unwrap().pop_front();
+        let response = response.unwrap_or_else(|| panic!("No response scripted for {}", url))?;
+        Ok(Response {
+            status: response.status,
+            headers: response.headers,
+            body: io::Cursor::new(response.body),
+        })
+    }
+}
@@ -0,0 +1,143 @@
+use std::fs::File;
+use tunein::{Document, Outline};
+
+const NESTED: &str = r#"<opml version="1"><body>
+    <outline text="Music" key="music">
+        <outline text="Rock" key="rock">
+            <outline type="audio" text="Rock FM" bitrate="128" reliability="95" guide_id="s1"/>
+            <outline type="audio" text="Rock AM" bitrate="64" reliability="99" guide_id="s2"/>
+        </outline>
+        <outline type="link" text="More rock" URL="http://opml.radiotime.com/Browse.ashx?id=g19" key="more" guide_id="g19"/>
+    </outline>
+    <outline text="Talk" key="talk">
+        <outline type="audio" text="Talk 24" bitrate="192" reliability="90" guide_id="s3"/>
+    </outline>
+    <outline type="text" text="No more stations"/>
+</body></opml>"#;
+
+fn nested() -> Document {
+    tunein::read(NESTED.as_bytes()).unwrap()
+}
+
+#[test]
+fn depth_first() {
+    let document = nested();
+    let visited: Vec<_> = document
+        .iter()
+        .map(|entry| {
+            let path: Vec<_> = entry.path.iter().map(|group| group.key.as_str()).collect();
+            (
+                entry.outline.text().to_string(),
+                entry.depth,
+                path.join("/"),
+            )
+        })
+        .collect();
+    let expected = vec![
+        ("Music", 0, ""),
+        ("Rock", 1, "music"),
+        ("Rock FM", 2, "music/rock"),
+        ("Rock AM", 2, "music/rock"),
+        ("More rock", 1, "music"),
+        ("Talk", 0, ""),
+        ("Talk 24", 1, "talk"),
+        ("No more stations", 0, ""),
+    ];
+    let expected: Vec<_> = expected
+        .into_iter()
+        .map(|(text, depth, path)| (text.to_string(), depth, path.to_string()))
+        .collect();
+    assert_eq!(visited, expected);
+}
+
+#[test]
+fn group_iter() {
+    let document = nested();
+    let music = match document.find_by_key("music") {
+        Some(Outline::Group(group)) => group,
+        outline => panic!("Unexpected outline {:?}", outline),
+    };
+    let depths: Vec<_> = music.iter().map(|entry| entry.depth).collect();
+    assert_eq!(depths, vec![0, 1, 1, 0]);
+    assert_eq!(music.audios().count(), 2);
+    assert_eq!(music.links().count(), 1);
+}
+
+#[test]
+fn audios_and_links() {
+    let document = tunein::read(File::open("tests/documents/sample_3.opml").unwrap()).unwrap();
+    let audios: Vec<_> = document.audios().map(|audio| audio.text.as_str()).collect();
+    assert_eq!(audios, vec!["L.A.C FM 95.5 (Portuguese Music)"]);
+    let links: Vec<_> = document.links().map(|link| link.text.as_str()).collect();
+    assert_eq!(links, vec!["Luanda"]);
+}
+
+#[test]
+fn bitrate_and_reliability_query() {
+    let document = nested();
+    let stations: Vec<_> = document
+        .audios()
+        .filter(|audio| audio.bitrate >= Some(128) && audio.reliability >= Some(90))
+        .map(|audio| audio.text.as_str())
+        .collect();
+    assert_eq!(stations, vec!["Rock FM", "Talk 24"]);
+}
+
+#[test]
+fn find_by_guide_id() {
+    let document = nested();
+    let found = document.find_by_guide_id(&"s2".parse().unwrap()).unwrap();
+    assert_eq!(found.text(), "Rock AM");
+    let found = document.find_by_guide_id(&"g19".parse().unwrap()).unwrap();
+    assert_eq!(found.text(), "More rock");
+    assert!(document.find_by_guide_id(&"s4".parse().unwrap()).is_none());
+}
+
+#[test]
+fn find_by_guide_id_in_describe() {
+    let document = tunein::read(File::open("tests/documents/describe_1.opml").unwrap()).unwrap();
+    let found = document.find_by_guide_id(&"s16527".parse().unwrap());
+    assert!(matches!(found, Some(Outline::Object(_))));
+}
+
+#[test]
+fn find_by_key() {
+    let document = nested();
+    assert_eq!(document.find_by_key("rock").unwrap().text(), "Rock");
+    assert_eq!(document.find_by_key("more").unwrap().text(), "More rock");
+    assert!(document.find_by_key("").is_none());
+}
+
+#[test]
+fn filter_keeps_structure() {
+    let document = nested();
+    let filtered = document.filter(|outline| match outline {
+        Outline::Audio(audio) => audio.bitrate >= Some(128),
+        _ => false,
+    });
+    let expected = tunein::read(
+        r#"<opml version="1"><body>
+            <outline text="Music" key="music">
+                <outline text="Rock" key="rock">
+                    <outline type="audio" text="Rock FM" bitrate="128" reliability="95" guide_id="s1"/>
+                </outline>
+            </outline>
+            <outline text="Talk" key="talk">
+                <outline type="audio" text="Talk 24" bitrate="192" reliability="90" guide_id="s3"/>
+            </outline>
+        </body></opml>"#
+            .as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(filtered, expected);
+}
+
+#[test]
+fn filter_matching_group() {
+    let document = nested();
+    let filtered = document.filter(|outline| outline.key() == "talk");
+    assert_eq!(filtered.outlines, vec![document.outlines[1].clone()]);
+
+    let filtered = document.filter(|_| false);
+    assert!(filtered.outlines.is_empty());
+}
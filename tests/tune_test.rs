@@ -0,0 +1,197 @@
+mod support;
+
+use support::FixtureTransport;
+use tunein::client::Client;
+use tunein::tune::{parse_playlist, PlaylistFormat, Stream};
+use tunein::{Audio, ErrorKind, Format, Url};
+
+fn read_fixture(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn stream(url: &str, title: Option<&str>, format: Option<Format>, bitrate: Option<u16>) -> Stream {
+    Stream {
+        url: Url::parse(url).unwrap(),
+        title: title.map(str::to_string),
+        format,
+        bitrate,
+    }
+}
+
+#[test]
+fn detect() {
+    let detect = |path| PlaylistFormat::detect(&read_fixture(path));
+    assert_eq!(detect("tests/documents/tune_1.m3u"), PlaylistFormat::M3U);
+    assert_eq!(detect("tests/documents/tune_2.pls"), PlaylistFormat::PLS);
+    assert_eq!(detect("tests/documents/tune_3.asx"), PlaylistFormat::ASX);
+    assert_eq!(detect("tests/documents/tune_4.txt"), PlaylistFormat::Plain);
+}
+
+#[test]
+fn m3u() {
+    let streams = parse_playlist(&read_fixture("tests/documents/tune_1.m3u"));
+    assert_eq!(
+        streams,
+        vec![
+            stream(
+                "http://ant-waw-01.cdn.eurozet.pl:8602/stream.mp3",
+                Some("Anty Radio (128kbps)"),
+                Some(Format::MP3),
+                Some(128),
+            ),
+            stream(
+                "http://ant-waw-02.cdn.eurozet.pl:8602/",
+                Some("Anty Radio backup"),
+                None,
+                None,
+            ),
+        ]
+    );
+}
+
+#[test]
+fn pls() {
+    let streams = parse_playlist(&read_fixture("tests/documents/tune_2.pls"));
+    assert_eq!(
+        streams,
+        vec![
+            stream(
+                "http://stream.rcs.revma.com/krkfm.aac",
+                Some("KRK.FM"),
+                Some(Format::AAC),
+                None,
+            ),
+            stream(
+                "http://stream.rcs.revma.com/ypqt40u0x1zuv",
+                Some("KRK.FM (AAC 64k)"),
+                None,
+                Some(64),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn asx() {
+    let streams = parse_playlist(&read_fixture("tests/documents/tune_3.asx"));
+    assert_eq!(
+        streams,
+        vec![
+            stream(
+                "mms://stream.radiokrakow.pl/nowysacz?a=1&b=2",
+                Some("PR R Krakow Nowy Sacz"),
+                Some(Format::WMA),
+                None,
+            ),
+            stream(
+                "http://stream.radiokrakow.pl/nowysacz.mp3",
+                Some("PR R Krakow Nowy Sacz"),
+                Some(Format::MP3),
+                None,
+            ),
+        ]
+    );
+}
+
+#[test]
+fn asx_attribute_syntax() {
+    let content = r#"<asx version="3.0"><entry>
+        <ref xhref="http://example.com/wrong.mp3" href = "http://example.com/spaced.mp3"/>
+        <ref
+            href=
+            'http://example.com/wrapped.aac'/>
+        <ref xhref="http://example.com/only.mp3"/>
+    </entry></asx>"#;
+    let urls: Vec<String> = parse_playlist(content)
+        .into_iter()
+        .map(|stream| stream.url.to_string())
+        .collect();
+    assert_eq!(
+        urls,
+        vec![
+            "http://example.com/spaced.mp3",
+            "http://example.com/wrapped.aac"
+        ]
+    );
+}
+
+#[test]
+fn plain() {
+    let streams = parse_playlist(&read_fixture("tests/documents/tune_4.txt"));
+    assert_eq!(
+        streams,
+        vec![
+            stream("http://radio.lac.co.ao:8000/live", None, None, None),
+            stream(
+                "http://radio.lac.co.ao:8000/live.ogg",
+                None,
+                Some(Format::OGG),
+                None,
+            ),
+        ]
+    );
+}
+
+#[test]
+fn skips_invalid_urls() {
+    let streams = parse_playlist("#EXTM3U\nnot a url\n/relative.mp3\nhttp://example.com/a\n");
+    assert_eq!(
+        streams,
+        vec![stream("http://example.com/a", None, None, None)]
+    );
+}
+
+fn client() -> Client<FixtureTransport> {
+    Client::new(
+        FixtureTransport::new()
+            .file(
+                "http://opml.radiotime.com/Tune.ashx?id=s76368",
+                "tests/documents/tune_1.m3u",
+            )
+            .file(
+                "http://opml.radiotime.com/Tune.ashx?id=s103067",
+                "tests/documents/tune_3.asx",
+            )
+            .body("http://opml.radiotime.com/Tune.ashx?id=s6228", 200, "\n"),
+    )
+}
+
+#[test]
+fn resolve() {
+    let url = Url::parse("http://opml.radiotime.com/Tune.ashx?id=s103067").unwrap();
+    let streams = client().resolve(&url).unwrap();
+    assert_eq!(streams.len(), 2);
+    assert_eq!(streams[1].format, Some(Format::MP3));
+}
+
+#[test]
+fn resolve_audio() {
+    let audio = Audio {
+        url: "http://opml.radiotime.com/Tune.ashx?id=s76368".to_string(),
+        bitrate: Some(96),
+        formats: vec![Format::MP3],
+        ..Default::default()
+    };
+    let streams = client().resolve_audio(&audio).unwrap();
+    assert_eq!(streams[0].bitrate, Some(128));
+    assert_eq!(streams[1].bitrate, Some(96));
+    assert_eq!(streams[1].format, Some(Format::MP3));
+}
+
+#[test]
+fn empty_playlist() {
+    let url = Url::parse("http://opml.radiotime.com/Tune.ashx?id=s6228").unwrap();
+    let error = client().resolve(&url).unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::EmptyPlaylist));
+}
+
+#[test]
+fn invalid_audio_url() {
+    let audio = Audio {
+        url: "Tune.ashx?id=s6228".to_string(),
+        ..Default::default()
+    };
+    let error = client().resolve_audio(&audio).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid URL \"Tune.ashx?id=s6228\"");
+    assert!(client().transport().requests().is_empty());
+}
@@ -0,0 +1,170 @@
+use tunein::visit::{walk_group, walk_outlines_mut, Visitor, VisitorMut};
+use tunein::{Audio, Document, Group, Link, Outline};
+
+const NESTED: &str = r#"<opml version="1"><body>
+    <outline text="Music" key="music">
+        <outline text="Rock" key="rock">
+            <outline type="audio" text="Rock FM" URL="http://opml.radiotime.com/Tune.ashx?id=s1" bitrate="128"/>
+            <outline type="audio" text="Rock AM" URL="http://opml.radiotime.com/Tune.ashx?id=s2" bitrate="64"/>
+        </outline>
+        <outline type="link" text="More rock" URL="http://opml.radiotime.com/Browse.ashx?id=g19"/>
+    </outline>
+    <outline type="audio" text="Talk 24" URL="http://opml.radiotime.com/Tune.ashx?id=s3" bitrate="192"/>
+    <outline type="text" text="No more stations"/>
+</body></opml>"#;
+
+fn nested() -> Document {
+    tunein::read(NESTED.as_bytes()).unwrap()
+}
+
+#[derive(Default)]
+struct Counter {
+    groups: Vec<String>,
+    audios: usize,
+    links: usize,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl Visitor for Counter {
+    fn visit_group(&mut self, group: &Group) {
+        self.groups.push(group.text.clone());
+        self.depth += 1;
+        self.max_depth = self.max_depth.max(self.depth);
+        walk_group(self, group);
+        self.depth -= 1;
+    }
+
+    fn visit_link(&mut self, _link: &Link) {
+        self.links += 1;
+    }
+
+    fn visit_audio(&mut self, _audio: &Audio) {
+        self.audios += 1;
+    }
+}
+
+#[test]
+fn visitor() {
+    let mut counter = Counter::default();
+    counter.visit_document(&nested());
+    assert_eq!(counter.groups, vec!["Music", "Rock"]);
+    assert_eq!(counter.audios, 3);
+    assert_eq!(counter.links, 1);
+    assert_eq!(counter.max_depth, 2);
+}
+
+struct Proxy;
+
+impl VisitorMut for Proxy {
+    fn visit_link_mut(&mut self, link: &mut Link) {
+        link.url = format!("http://proxy.local/?u={}", link.url);
+    }
+
+    fn visit_audio_mut(&mut self, audio: &mut Audio) {
+        audio.url = format!("http://proxy.local/?u={}", audio.url);
+    }
+}
+
+#[test]
+fn visitor_mut() {
+    let mut document = nested();
+    Proxy.visit_document_mut(&mut document);
+    let audio = document.audios().next().unwrap();
+    assert_eq!(
+        audio.url,
+        "http://proxy.local/?u=http://opml.radiotime.com/Tune.ashx?id=s1"
+    );
+    assert!(document
+        .links()
+        .all(|link| link.url.starts_with("http://proxy.local/")));
+}
+
+struct Flatten;
+
+impl VisitorMut for Flatten {
+    fn visit_outlines_mut(&mut self, outlines: &mut Vec<Outline>) {
+        walk_outlines_mut(self, outlines);
+        let mut flat = Vec::with_capacity(outlines.len());
+        for outline in outlines.drain(..) {
+            match outline {
+                Outline::Group(group) => flat.extend(group.outlines),
+                outline => flat.push(outline),
+            }
+        }
+        *outlines = flat;
+    }
+}
+
+#[test]
+fn flatten() {
+    let mut document = nested();
+    Flatten.visit_document_mut(&mut document);
+    let texts: Vec<_> = document.outlines.iter().map(Outline::text).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "Rock FM",
+            "Rock AM",
+            "More rock",
+            "Talk 24",
+            "No more stations"
+        ]
+    );
+}
+
+#[test]
+fn retain() {
+    let mut document = nested();
+    let mut checked = vec![];
+    document.retain(|outline| {
+        checked.push(outline.text().to_string());
+        match outline {
+            Outline::Audio(audio) => audio.bitrate >= Some(128),
+            Outline::Text(_) => false,
+            _ => true,
+        }
+    });
+    let audios: Vec<_> = document.audios().map(|audio| audio.text.as_str()).collect();
+    assert_eq!(audios, vec!["Rock FM", "Talk 24"]);
+    assert_eq!(document.outlines.len(), 2);
+    assert_eq!(
+        checked,
+        vec![
+            "Music",
+            "Talk 24",
+            "No more stations",
+            "Rock",
+            "More rock",
+            "Rock FM",
+            "Rock AM"
+        ]
+    );
+}
+
+#[test]
+fn retain_removes_whole_groups() {
+    let mut document = nested();
+    document.retain(|outline| outline.key() != "music");
+    assert!(document.find_by_key("rock").is_none());
+    assert_eq!(document.audios().count(), 1);
+}
+
+#[test]
+fn map() {
+    let mut groups = vec![];
+    let document = nested().map(|outline| match outline {
+        Outline::Audio(mut audio) => {
+            audio.text = audio.text.to_uppercase();
+            Outline::Audio(audio)
+        }
+        Outline::Group(group) => {
+            groups.push(group.outlines[0].text().to_string());
+            Outline::Group(group)
+        }
+        outline => outline,
+    });
+    let audios: Vec<_> = document.audios().map(|audio| audio.text.as_str()).collect();
+    assert_eq!(audios, vec!["ROCK FM", "ROCK AM", "TALK 24"]);
+    assert_eq!(groups, vec!["ROCK FM", "Rock"]);
+}
@@ -0,0 +1,137 @@
+use std::fs::File;
+use tunein::{Document, Event, Group, Link, Outline, Reader, Writer};
+
+fn round_trip(path: &str) {
+    let document = tunein::read(File::open(path).unwrap()).unwrap();
+
+    let mut buffer = Vec::new();
+    tunein::write(&document, &mut buffer).unwrap();
+
+    assert_eq!(tunein::read(buffer.as_slice()).unwrap(), document);
+}
+
+#[test]
+fn sample_1_round_trip() {
+    round_trip("tests/documents/sample_1.opml");
+}
+
+#[test]
+fn sample_2_round_trip() {
+    round_trip("tests/documents/sample_2.opml");
+}
+
+#[test]
+fn sample_3_round_trip() {
+    round_trip("tests/documents/sample_3.opml");
+}
+
+#[test]
+fn describe_round_trip() {
+    round_trip("tests/documents/describe_1.opml");
+    round_trip("tests/documents/describe_2.opml");
+    round_trip("tests/documents/describe_3.opml");
+}
+
+#[test]
+fn tunein_attribute_names() {
+    let document = tunein::read(File::open("tests/documents/sample_3.opml").unwrap()).unwrap();
+
+    let mut buffer = Vec::new();
+    tunein::write(&document, &mut buffer).unwrap();
+    let output = String::from_utf8(buffer).unwrap();
+
+    assert!(output.contains("<opml version=\"1\">"));
+    assert!(output.contains("<title>Angola</title>"));
+    assert!(output.contains("<status>200</status>"));
+    assert!(output.contains("<outline text=\"All Stations\" key=\"stations\">"));
+    assert!(output.contains(
+        "<outline type=\"link\" text=\"Luanda\" \
+         URL=\"http://opml.radiotime.com/Browse.ashx?id=r100526\" guide_id=\"r100526\" />"
+    ));
+    assert!(output.contains("formats=\"mp3\""));
+    assert!(output.contains("now_playing_id=\"s6228\""));
+    assert!(output.contains("preset_id=\"s6228\""));
+}
+
+#[test]
+fn escaping() {
+    let input = "<opml version=\"1\"><body>\
+                 <outline type=\"link\" text=\"Rock &amp; Roll &quot;Hits&quot;\" \
+                 URL=\"http://opml.radiotime.com/Browse.ashx?id=g19&amp;offset=26\"/>\
+                 </body></opml>";
+    let document = tunein::read(input.as_bytes()).unwrap();
+
+    let mut buffer = Vec::new();
+    tunein::write(&document, &mut buffer).unwrap();
+
+    assert_eq!(tunein::read(buffer.as_slice()).unwrap(), document);
+}
+
+#[test]
+fn events_round_trip() {
+    let events: Vec<Event> = Reader::new(File::open("tests/documents/sample_2.opml").unwrap())
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let mut writer = Writer::new(Vec::new());
+    for event in &events {
+        writer.write(event).unwrap();
+    }
+    let buffer = writer.into_inner();
+
+    let written_events: Vec<Event> = Reader::new(buffer.as_slice())
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(written_events, events);
+}
+
+#[test]
+fn extra_attributes_named_like_known_ones() {
+    let mut link = Link {
+        text: "Rock".to_string(),
+        url: "http://opml.radiotime.com/Browse.ashx?id=g19".to_string(),
+        ..Link::default()
+    };
+    link.attributes.insert("text", "Other");
+    link.attributes.insert("URL", "http://example.com");
+    link.attributes.insert("genre", "rock");
+    let mut group = Group {
+        text: "Music".to_string(),
+        ..Group::default()
+    };
+    group.attributes.insert("text", "Other");
+    group
+        .attributes
+        .insert("URL", "http://opml.radiotime.com/Browse.ashx?c=music");
+    group.outlines.push(Outline::Link(link));
+    let document = Document {
+        outlines: vec![Outline::Group(group)],
+        ..Document::default()
+    };
+
+    let mut buffer = Vec::new();
+    tunein::write(&document, &mut buffer).unwrap();
+    let read = tunein::read(buffer.as_slice()).unwrap();
+
+    let group = match &read.outlines[0] {
+        Outline::Group(group) => group,
+        outline => panic!("Unexpected outline {:?}", outline),
+    };
+    assert_eq!(group.text, "Music");
+    assert_eq!(group.attributes.get("text"), None);
+    assert_eq!(
+        group.attributes.get("URL"),
+        Some("http://opml.radiotime.com/Browse.ashx?c=music")
+    );
+    match &group.outlines[0] {
+        Outline::Link(link) => {
+            assert_eq!(link.text, "Rock");
+            assert_eq!(link.url, "http://opml.radiotime.com/Browse.ashx?id=g19");
+            assert_eq!(link.attributes.get("genre"), Some("rock"));
+            assert_eq!(link.attributes.get("URL"), None);
+        }
+        outline => panic!("Unexpected outline {:?}", outline),
+    }
+}